@@ -0,0 +1,239 @@
+use super::{
+    solvers::{
+        utils::calculate_practical_ftpas_error, ApproxPruning, ApproxPruningSolver, FTPASScaling,
+        FTPASSolver, Methods, Solver, SolverTrait, FTPAS,
+    },
+    DisplayError, Opts, Problem, ProblemFromfile, Solution, SolutionsFromFile,
+};
+use std::collections::HashMap;
+use std::time::Instant;
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+pub struct AnalyzeOpts {
+    // ftpas (precision is divisor of costs) or approx-pruning
+    method: Methods,
+    input_task: ProblemFromfile,
+    solution: SolutionsFromFile,
+    // swept precisions start..=end
+    #[structopt(long, default_value = "1")]
+    start: u32,
+    #[structopt(long, default_value = "10")]
+    end: u32,
+    #[structopt(long, default_value = "1")]
+    step: u32,
+}
+
+// errors and times of all problems solved with one precision
+#[derive(Debug, Default)]
+struct Row {
+    // problems with worse solution than reference
+    errors: usize,
+    total_error: u64,
+    max_error: u32,
+    // absolute error / error guaranteed by precision
+    total_bound_ratio: f64,
+    max_bound_ratio: f64,
+    total_time: f64,
+    max_time: f64,
+}
+
+fn solver(method: Methods, precision: u32) -> Result<Solver, DisplayError> {
+    match method {
+        Methods::FTPAS => Ok(FTPAS(FTPASSolver {
+            scaling: FTPASScaling::Divisor(precision),
+        })),
+        Methods::ApproxPruning => Ok(ApproxPruning(ApproxPruningSolver { precision })),
+        _ => Err(format!(
+            "Method {} has no precision to analyze, use ftpas or approx-pruning.",
+            method
+        )
+        .into()),
+    }
+}
+
+// error, which solver with precision can make on problem with optimal cost
+fn error_bound(solver: &Solver, problem: &Problem, optimum: u32, precision: u32) -> u64 {
+    match solver {
+        FTPAS(_) => calculate_practical_ftpas_error(problem, precision),
+        // approx-pruning cuts branch, when its upper bound is at most best * (p + 1) / p
+        // (best_cost_bigger in approx_pruning.rs). Best cost only grows, so final cost c
+        // is at least best of every cut, and optimum is below bound of some cut branch,
+        // unless it was found: opt <= c * (p + 1) / p, so opt - c <= opt / (p + 1)
+        _ => (optimum / (precision + 1)) as u64,
+    }
+}
+
+fn row(
+    solver: &Solver,
+    precision: u32,
+    problems: &[Problem],
+    references: &HashMap<u32, &Solution>,
+    opts: &Opts,
+) -> Result<Row, DisplayError> {
+    // problems over memory limit are not analyzed, because fallback is exact
+    let fallback = Solver::memory_fallback(opts);
+    for problem in problems {
+        if solver.within_memory_limit(&fallback, problem, opts)?.1 {
+            return Err(format!(
+                "Problem {} is over memory limit and its fallback pruning has no error to analyze.",
+                problem.id
+            )
+            .into());
+        }
+    }
+    let mut row = Row::default();
+    for problem in problems {
+        let reference = references
+            .get(&problem.id)
+            .ok_or_else(|| format!("Missing reference solution of problem {}.", problem.id))?;
+        let start = Instant::now();
+        let solution = solver.construction(problem);
+        let time = start.elapsed().as_secs_f64();
+        row.total_time += time;
+        row.max_time = row.max_time.max(time);
+
+        let error = reference.cost.saturating_sub(solution.cost);
+        if error > 0 {
+            let bound = error_bound(solver, problem, reference.cost, precision);
+            let bound_ratio = error as f64 / bound.max(1) as f64;
+            row.errors += 1;
+            row.total_error += error as u64;
+            row.max_error = row.max_error.max(error);
+            row.total_bound_ratio += bound_ratio;
+            row.max_bound_ratio = row.max_bound_ratio.max(bound_ratio);
+        }
+    }
+    Ok(row)
+}
+
+pub fn analyze(opts: &Opts, analyze_opts: &AnalyzeOpts) -> Result<(), DisplayError> {
+    if analyze_opts.start == 0 || analyze_opts.step == 0 {
+        return Err("Start and step of precisions have to be positive.".into());
+    }
+    // method is checked before header is printed
+    solver(analyze_opts.method, analyze_opts.start)?;
+    let references = analyze_opts
+        .solution
+        .0
+        .iter()
+        .map(|solution| (solution.id, solution))
+        .collect::<HashMap<_, _>>();
+    let problems = &analyze_opts.input_task.0;
+
+    println!(
+        "precision errors avg-error max-error avg-bound-ratio max-bound-ratio avg-time max-time"
+    );
+    for precision in (analyze_opts.start..=analyze_opts.end).step_by(analyze_opts.step as usize) {
+        let row = row(
+            &solver(analyze_opts.method, precision)?,
+            precision,
+            problems,
+            &references,
+            opts,
+        )?;
+        // errors are averaged over problems with error, times over all problems
+        let erroneous = row.errors.max(1) as f64;
+        println!(
+            "{} {} {} {} {} {} {} {}",
+            precision,
+            row.errors,
+            row.total_error as f64 / erroneous,
+            row.max_error,
+            row.total_bound_ratio / erroneous,
+            row.max_bound_ratio,
+            row.total_time / problems.len().max(1) as f64,
+            row.max_time
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::generator::{generate, GenerateOpts};
+    use super::*;
+
+    // problems with large costs, so errors of both methods are common
+    fn problems() -> Vec<Problem> {
+        let opts = GenerateOpts::from_iter_safe(&[
+            "generate",
+            "--count=40",
+            "--size=15",
+            "--max-cost=1000",
+            "--correlation=corr",
+        ])
+        .unwrap();
+        generate(&opts, 23).unwrap()
+    }
+
+    fn opts(args: &[&str]) -> Opts {
+        Opts::from_iter_safe(["knapsack"].iter().chain(args)).unwrap()
+    }
+
+    fn optima(problems: &[Problem]) -> Vec<Solution> {
+        let solver = Solver::from_method(Methods::DynamicWeight, &opts(&[])).unwrap();
+        problems
+            .iter()
+            .map(|problem| solver.construction(problem))
+            .collect()
+    }
+
+    #[test]
+    fn errors_never_exceed_bound() {
+        let problems = problems();
+        let optima = optima(&problems);
+        let references = optima
+            .iter()
+            .map(|solution| (solution.id, solution))
+            .collect::<HashMap<_, _>>();
+        for &method in &[Methods::FTPAS, Methods::ApproxPruning] {
+            let mut errors = 0;
+            for precision in (1..=10).chain(vec![20, 50, 100]) {
+                let row = row(
+                    &solver(method, precision).unwrap(),
+                    precision,
+                    &problems,
+                    &references,
+                    &opts(&[]),
+                )
+                .unwrap();
+                assert!(
+                    row.max_bound_ratio <= 1.0,
+                    "{} {} {:?}",
+                    method,
+                    precision,
+                    row
+                );
+                errors += row.errors;
+            }
+            // sweep has to make some errors to check anything
+            assert!(errors > 0, "{}", method);
+        }
+    }
+
+    #[test]
+    fn missing_reference_and_exact_method_are_errors() {
+        let problems = problems();
+        let references = HashMap::new();
+        let ftpas = solver(Methods::FTPAS, 1).unwrap();
+        assert!(row(&ftpas, 1, &problems, &references, &opts(&[])).is_err());
+        assert!(solver(Methods::Pruning, 1).is_err());
+    }
+
+    #[test]
+    fn memory_limit_is_checked() {
+        let problems = problems();
+        let optima = optima(&problems);
+        let references = optima
+            .iter()
+            .map(|solution| (solution.id, solution))
+            .collect::<HashMap<_, _>>();
+        let ftpas = solver(Methods::FTPAS, 1).unwrap();
+        let row = |args: &[&str]| row(&ftpas, 1, &problems, &references, &opts(args));
+        assert!(row(&["--memory-limit=1G"]).is_ok());
+        assert!(row(&["--memory-limit=1k"]).is_err());
+        // fallback would be exact and hide errors
+        assert!(row(&["--memory-limit=1k", "--memory-fallback"]).is_err());
+    }
+}
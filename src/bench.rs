@@ -0,0 +1,348 @@
+use super::{
+    solvers::{Methods, Solver, SolverTrait},
+    DisplayError, Opts, Problem, ProblemFromfile,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Instant;
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+pub struct BenchOpts {
+    // instance files named <set><size>_inst.dat
+    #[structopt(required = true)]
+    files: Vec<BenchFile>,
+    #[structopt(
+        long,
+        use_delimiter = true,
+        default_value = "pruning,dynamic-cost,dynamic-weight"
+    )]
+    methods: Vec<Methods>,
+    // unmeasured runs of whole file before measured ones
+    #[structopt(long, default_value = "1")]
+    warmup: usize,
+    // maximal number of measured runs
+    #[structopt(long, default_value = "10")]
+    repeats: usize,
+    // no more runs are started, after measured runs took this many seconds
+    #[structopt(long, default_value = "0.1")]
+    min_time: f64,
+    #[structopt(long, default_value = "data.json")]
+    pub output: PathBuf,
+}
+
+#[derive(Debug)]
+pub struct BenchFile {
+    set: String,
+    size: String,
+    problems: Vec<Problem>,
+}
+
+impl FromStr for BenchFile {
+    type Err = DisplayError;
+    fn from_str(file_name: &str) -> Result<BenchFile, DisplayError> {
+        let stem = Path::new(file_name)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix("_inst.dat"))
+            .unwrap_or("");
+        let digits = stem.chars().rev().take_while(char::is_ascii_digit).count();
+        if digits == 0 || digits == stem.len() {
+            return Err(format!(
+                "Instance file {:?} is not named <set><size>_inst.dat.",
+                file_name
+            )
+            .into());
+        }
+        let (set, size) = stem.split_at(stem.len() - digits);
+        Ok(BenchFile {
+            set: set.to_string(),
+            size: size.to_string(),
+            problems: file_name.parse::<ProblemFromfile>()?.0,
+        })
+    }
+}
+
+// times of one method on one file in seconds, graph.py plots avg and max,
+// json of runner.py has only them
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct BenchStats {
+    pub avg: f64,
+    // mean of maximal times of runs
+    pub max: f64,
+    pub median: f64,
+    pub stddev: f64,
+    // half width of 95% confidence interval of avg
+    pub ci95: f64,
+    pub runs: usize,
+    // mean time of every problem, for histograms
+    pub times: Vec<f64>,
+}
+
+// set -> size -> method -> stats
+pub type BenchData = BTreeMap<String, BTreeMap<String, BTreeMap<String, BenchStats>>>;
+
+// times of all problems of file, each solved by its solver
+fn run(solvers: &[&Solver], problems: &[Problem], force_construction: bool) -> Vec<f64> {
+    problems
+        .iter()
+        .zip(solvers)
+        .map(|(problem, solver)| {
+            let start = Instant::now();
+            let solution = match problem.min_cost.is_none() || force_construction {
+                true => solver.construction(problem),
+                false => solver.decision(problem),
+            };
+            let elapsed = start.elapsed().as_secs_f64();
+            black_box(solution);
+            elapsed
+        })
+        .collect()
+}
+
+fn stats(runs: &[Vec<f64>]) -> BenchStats {
+    let mut times = runs.iter().flatten().cloned().collect::<Vec<_>>();
+    times.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let n = times.len() as f64;
+    let avg = times.iter().sum::<f64>() / n;
+    let variance = times.iter().map(|t| (t - avg) * (t - avg)).sum::<f64>() / (n - 1.0).max(1.0);
+    let middle = times.len() / 2;
+    BenchStats {
+        avg,
+        max: runs
+            .iter()
+            .map(|run| run.iter().cloned().fold(0.0, f64::max))
+            .sum::<f64>()
+            / runs.len() as f64,
+        median: if times.len() % 2 == 0 {
+            (times[middle - 1] + times[middle]) / 2.0
+        } else {
+            times[middle]
+        },
+        stddev: variance.sqrt(),
+        ci95: 1.96 * variance.sqrt() / n.sqrt(),
+        runs: runs.len(),
+        times: (0..runs[0].len())
+            .map(|i| runs.iter().map(|run| run[i]).sum::<f64>() / runs.len() as f64)
+            .collect(),
+    }
+}
+
+pub fn bench(opts: &Opts, bench_opts: &BenchOpts) -> Result<BenchData, DisplayError> {
+    let solvers = bench_opts
+        .methods
+        .iter()
+        .map(|&method| Ok((method, Solver::from_method(method, opts)?)))
+        .collect::<Result<Vec<_>, DisplayError>>()?;
+    if solvers.iter().any(|(_, solver)| solver.is_randomized()) {
+        println!("seed: {}", opts.seed.unwrap_or_default());
+    }
+
+    // memory limit of every file and method is checked before anything is measured
+    let fallback = Solver::memory_fallback(opts);
+    let files = bench_opts
+        .files
+        .iter()
+        .filter(|file| !file.problems.is_empty())
+        .map(|file| {
+            let file_solvers = solvers
+                .iter()
+                .map(|(method, solver)| {
+                    let problem_solvers = file
+                        .problems
+                        .iter()
+                        .map(|problem| Ok(solver.within_memory_limit(&fallback, problem, opts)?.0))
+                        .collect::<Result<Vec<_>, DisplayError>>()?;
+                    Ok((method, problem_solvers))
+                })
+                .collect::<Result<Vec<_>, DisplayError>>()?;
+            Ok((file, file_solvers))
+        })
+        .collect::<Result<Vec<_>, DisplayError>>()?;
+
+    let mut data = BenchData::new();
+    for (file, file_solvers) in files {
+        for (method, problem_solvers) in file_solvers {
+            for _ in 0..bench_opts.warmup {
+                run(&problem_solvers, &file.problems, opts.force_construction);
+            }
+            let mut runs: Vec<Vec<f64>> = vec![];
+            while runs.len() < bench_opts.repeats.max(1)
+                && runs.iter().flatten().sum::<f64>() <= bench_opts.min_time
+            {
+                runs.push(run(
+                    &problem_solvers,
+                    &file.problems,
+                    opts.force_construction,
+                ));
+            }
+            let stats = stats(&runs);
+            println!(
+                "{} {} {} {} {}",
+                file.set, file.size, method, stats.max, stats.avg
+            );
+            data.entry(file.set.clone())
+                .or_default()
+                .entry(file.size.clone())
+                .or_default()
+                .insert(method.to_string(), stats);
+        }
+    }
+    Ok(data)
+}
+
+pub fn read_bench(path: &Path) -> Result<BenchData, DisplayError> {
+    let json = fs::read_to_string(path)
+        .map_err(|e| format!("Could not load benchmark file: {:?}, because: {}", path, e))?;
+    serde_json::from_str(&json)
+        .map_err(|e| format!("Could not parse benchmark file: {:?}, because: {}", path, e).into())
+}
+
+pub fn write_bench(path: &Path, data: &BenchData) -> Result<(), DisplayError> {
+    fs::write(path, serde_json::to_string_pretty(data).unwrap())
+        .map_err(|e| format!("Could not write benchmark file: {:?}, because: {}", path, e).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{solvers::METHODS, Command};
+    use super::*;
+
+    // instance file in temp dir, removed when dropped
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str, content: &str) -> TempFile {
+            let path =
+                std::env::temp_dir().join(format!("knapsack-{}-{}", std::process::id(), name));
+            fs::write(&path, content).unwrap();
+            TempFile(path)
+        }
+
+        fn name(&self) -> &str {
+            self.0.to_str().unwrap()
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    const INSTANCES: &str = "1 4 10 2 3 4 5 6 7 8 9\n2 4 12 3 3 4 4 5 9 6 7\n";
+
+    #[test]
+    fn method_and_file_parse_next_to_subcommands() {
+        let file = TempFile::new("parse4_inst.dat", INSTANCES);
+        // genetic is close to subcommand generate
+        METHODS.iter().for_each(|(name, method)| {
+            let opts = Opts::from_iter_safe(&["knapsack", name, file.name()]).unwrap();
+            assert_eq!(opts.method, Some(*method), "{}", name);
+            assert_eq!(opts.input_task.unwrap().0.len(), 2, "{}", name);
+            assert!(opts.command.is_none(), "{}", name);
+        });
+
+        // options of knapsack go before subcommand
+        let opts =
+            Opts::from_iter_safe(&["knapsack", "--seed=3", "bench", "--repeats=2", file.name()])
+                .unwrap();
+        assert_eq!(opts.seed, Some(3));
+        assert!(opts.method.is_none());
+        match opts.command {
+            Some(Command::Bench(bench_opts)) => {
+                assert_eq!(bench_opts.repeats, 2);
+                assert_eq!(
+                    bench_opts.files[0].set,
+                    format!("knapsack-{}-parse", std::process::id())
+                );
+                assert_eq!(bench_opts.files[0].size, "4");
+            }
+            command => panic!("wrong command {:?}", command),
+        }
+        assert!(Opts::from_iter_safe(&["knapsack", "pruning", "missing_inst.dat"]).is_err());
+    }
+
+    #[test]
+    fn memory_limit_is_checked_before_measuring() {
+        let file = TempFile::new("limit4_inst.dat", INSTANCES);
+        let bench_with = |args: &[&str]| {
+            let mut all = vec!["knapsack"];
+            all.extend_from_slice(args);
+            all.extend_from_slice(&["bench", "--methods=dynamic-cost", file.name()]);
+            let opts = Opts::from_iter_safe(&all).unwrap();
+            match &opts.command {
+                Some(Command::Bench(bench_opts)) => bench(&opts, bench_opts),
+                _ => unreachable!(),
+            }
+        };
+        assert!(bench_with(&["--memory-limit=1"]).is_err());
+        assert!(bench_with(&["--memory-limit=1", "--memory-fallback"]).is_ok());
+        assert!(bench_with(&["--memory-limit=1M"]).is_ok());
+    }
+
+    #[test]
+    fn file_name_has_set_and_size() {
+        assert!("set_inst.dat".parse::<BenchFile>().is_err());
+        assert!("40_inst.dat".parse::<BenchFile>().is_err());
+        assert!("corr40.dat".parse::<BenchFile>().is_err());
+    }
+
+    #[test]
+    fn stats_of_runs() {
+        let stats = stats(&[vec![1.0, 3.0], vec![3.0, 5.0]]);
+        assert_eq!(stats.avg, 3.0);
+        // mean of maximal times 3 and 5
+        assert_eq!(stats.max, 4.0);
+        assert_eq!(stats.median, 3.0);
+        assert!((stats.stddev - (8.0f64 / 3.0).sqrt()).abs() < 1e-12);
+        assert!((stats.ci95 - 1.96 * stats.stddev / 2.0).abs() < 1e-12);
+        assert_eq!(stats.runs, 2);
+        assert_eq!(stats.times, vec![2.0, 4.0]);
+        assert_eq!(super::stats(&[vec![2.0]]).stddev, 0.0);
+    }
+
+    #[test]
+    fn bench_writes_every_method_and_reads_back() {
+        let file = TempFile::new("corr4_inst.dat", INSTANCES);
+        let output = TempFile::new("data.json", "");
+        let opts = Opts::from_iter_safe(&[
+            "knapsack",
+            "bench",
+            "--methods=pruning,dynamic-cost",
+            "--repeats=3",
+            "--min-time=10",
+            file.name(),
+        ])
+        .unwrap();
+        let bench_opts = match &opts.command {
+            Some(Command::Bench(bench_opts)) => bench_opts,
+            _ => unreachable!(),
+        };
+        let data = bench(&opts, bench_opts).unwrap();
+        let methods = &data[&format!("knapsack-{}-corr", std::process::id())]["4"];
+        assert_eq!(
+            methods.keys().collect::<Vec<_>>(),
+            vec!["dynamic-cost", "pruning"]
+        );
+        methods.values().for_each(|stats| {
+            assert_eq!(stats.runs, 3);
+            assert_eq!(stats.times.len(), 2);
+        });
+        write_bench(&output.0, &data).unwrap();
+        // json keeps about 15 digits of times
+        let read = read_bench(&output.0).unwrap();
+        let read_methods = &read[&format!("knapsack-{}-corr", std::process::id())]["4"];
+        methods.iter().for_each(|(method, stats)| {
+            let read_stats = &read_methods[method];
+            assert_eq!(read_stats.runs, stats.runs);
+            assert!((read_stats.avg - stats.avg).abs() <= 1e-12 * stats.avg);
+            assert_eq!(read_stats.times.len(), stats.times.len());
+        });
+    }
+}
@@ -0,0 +1,352 @@
+use super::{DisplayError, Item, Problem};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::str::FromStr;
+use structopt::StructOpt;
+
+// correlation of cost to weight
+#[derive(Debug, Clone, Copy)]
+pub enum Correlation {
+    Uni,
+    // cost is scaled weight with noise of tenth of max cost, but at least 1
+    Corr,
+    // cost is scaled weight plus tenth of max cost
+    Strong,
+}
+
+impl FromStr for Correlation {
+    type Err = DisplayError;
+    fn from_str(name: &str) -> Result<Correlation, DisplayError> {
+        match name {
+            "uni" => Ok(Correlation::Uni),
+            "corr" => Ok(Correlation::Corr),
+            "strong" => Ok(Correlation::Strong),
+            _ => Err(format!(
+                "Correlation {:?} not found, following are valid: uni, corr, strong.",
+                name
+            )
+            .into()),
+        }
+    }
+}
+
+// prevalence of light or heavy items
+#[derive(Debug, Clone, Copy)]
+pub enum Balance {
+    Bal,
+    Light,
+    Heavy,
+}
+
+impl FromStr for Balance {
+    type Err = DisplayError;
+    fn from_str(name: &str) -> Result<Balance, DisplayError> {
+        match name {
+            "bal" => Ok(Balance::Bal),
+            "light" => Ok(Balance::Light),
+            "heavy" => Ok(Balance::Heavy),
+            _ => Err(format!(
+                "Balance {:?} not found, following are valid: bal, light, heavy.",
+                name
+            )
+            .into()),
+        }
+    }
+}
+
+// hard instances by Pisinger, classes defined only by weights ignore max cost
+#[derive(Debug, Clone, Copy)]
+pub enum Class {
+    // weight is scaled cost plus tenth of max weight, rounded up
+    InverseStrong,
+    // strongly correlated with small noise
+    AlmostStrong,
+    // cost equals weight
+    SubsetSum,
+    // items are multiples of few spanner items generated by correlation
+    Spanner,
+    // cost is weight rounded up to multiple of 3
+    ProfitCeiling,
+    // cost is on ellipse above weight
+    Circle,
+}
+
+impl FromStr for Class {
+    type Err = DisplayError;
+    fn from_str(name: &str) -> Result<Class, DisplayError> {
+        match name {
+            "inverse-strong" => Ok(Class::InverseStrong),
+            "almost-strong" => Ok(Class::AlmostStrong),
+            "subset-sum" => Ok(Class::SubsetSum),
+            "spanner" => Ok(Class::Spanner),
+            "profit-ceiling" => Ok(Class::ProfitCeiling),
+            "circle" => Ok(Class::Circle),
+            _ => Err(format!(
+                "Class {:?} not found, following are valid: inverse-strong, almost-strong, subset-sum, spanner, profit-ceiling, circle.",
+                name
+            )
+            .into()),
+        }
+    }
+}
+
+#[derive(StructOpt, Debug)]
+pub struct GenerateOpts {
+    // items of every instance
+    #[structopt(long, default_value = "20")]
+    size: usize,
+    // number of instances
+    #[structopt(long, default_value = "50")]
+    count: usize,
+    #[structopt(long, default_value = "100")]
+    max_weight: u32,
+    #[structopt(long, default_value = "250")]
+    max_cost: u32,
+    // capacity / total weight
+    #[structopt(long, default_value = "0.5")]
+    capacity_ratio: f64,
+    #[structopt(long, default_value = "uni")]
+    correlation: Correlation,
+    #[structopt(long, default_value = "bal")]
+    balance: Balance,
+    // exponent of light and heavy balance
+    #[structopt(short, default_value = "1")]
+    k: f64,
+    // replaces correlation, spanner items are still generated by it
+    #[structopt(long)]
+    class: Option<Class>,
+    // overrides --seed of knapsack
+    #[structopt(long)]
+    pub seed: Option<u64>,
+}
+
+// spanner items and their maximal multiplier
+const SPANNERS: usize = 2;
+const SPANNER_MULTIPLIER: u32 = 10;
+
+fn weight(opts: &GenerateOpts, rng: &mut StdRng) -> u32 {
+    let u: f64 = rng.gen();
+    let u = match opts.balance {
+        Balance::Bal => u,
+        Balance::Light => u.powf(1.0 + opts.k),
+        Balance::Heavy => 1.0 - u.powf(1.0 + opts.k),
+    };
+    (1 + (u * opts.max_weight as f64) as u32).min(opts.max_weight)
+}
+
+fn correlated_item(opts: &GenerateOpts, rng: &mut StdRng) -> Item {
+    let weight = weight(opts, rng);
+    // rounded up, so every item has positive cost
+    let scaled = (weight as u64 * opts.max_cost as u64).div_ceil(opts.max_weight as u64) as u32;
+    let tenth = opts.max_cost / 10;
+    let cost = match opts.correlation {
+        Correlation::Uni => rng.gen_range(1, opts.max_cost + 1),
+        // noise is drawn only from positive costs
+        Correlation::Corr => rng.gen_range(scaled.saturating_sub(tenth).max(1), scaled + tenth + 1),
+        Correlation::Strong => scaled + tenth,
+    };
+    Item { weight, cost }
+}
+
+fn class_item(opts: &GenerateOpts, class: Class, spanners: &[Item], rng: &mut StdRng) -> Item {
+    let max_weight = opts.max_weight as f64;
+    let max_cost = opts.max_cost as f64;
+    match class {
+        Class::InverseStrong => {
+            let cost = rng.gen_range(1, opts.max_cost + 1);
+            Item {
+                weight: (cost as f64 * max_weight / max_cost + max_weight / 10.0).ceil() as u32,
+                cost,
+            }
+        }
+        Class::AlmostStrong => {
+            let weight = weight(opts, rng);
+            let noise = max_cost / 500.0;
+            let base = weight as f64 * max_cost / max_weight + max_cost / 10.0;
+            Item {
+                weight,
+                cost: rng.gen_range((base - noise).max(1.0), base + noise + 1.0) as u32,
+            }
+        }
+        Class::SubsetSum => {
+            let weight = weight(opts, rng);
+            Item {
+                weight,
+                cost: weight,
+            }
+        }
+        Class::Spanner => {
+            let spanner = spanners[rng.gen_range(0, spanners.len())];
+            let multiplier = rng.gen_range(1, SPANNER_MULTIPLIER + 1);
+            Item {
+                weight: spanner.weight * multiplier,
+                cost: spanner.cost * multiplier,
+            }
+        }
+        Class::ProfitCeiling => {
+            let weight = weight(opts, rng);
+            Item {
+                weight,
+                cost: weight.div_ceil(3) * 3,
+            }
+        }
+        Class::Circle => {
+            let weight = weight(opts, rng);
+            let x = weight as f64 - 2.0 * max_weight;
+            Item {
+                weight,
+                cost: (2.0 / 3.0 * (4.0 * max_weight * max_weight - x * x).sqrt()) as u32,
+            }
+        }
+    }
+}
+
+pub fn generate(opts: &GenerateOpts, seed: u64) -> Result<Vec<Problem>, DisplayError> {
+    if opts.max_weight == 0 || opts.max_cost == 0 {
+        return Err("Max weight and max cost have to be positive.".into());
+    }
+    let mut rng = StdRng::seed_from_u64(seed);
+    Ok((1..=opts.count as u32)
+        .map(|id| {
+            // spanner items are shrunk, so their multiples stay in range
+            let spanners = (0..SPANNERS)
+                .map(|_| {
+                    let item = correlated_item(opts, &mut rng);
+                    Item {
+                        weight: (2 * item.weight).div_ceil(SPANNER_MULTIPLIER),
+                        cost: (2 * item.cost).div_ceil(SPANNER_MULTIPLIER),
+                    }
+                })
+                .collect::<Vec<_>>();
+            let items = (0..opts.size)
+                .map(|_| match opts.class {
+                    Some(class) => class_item(opts, class, &spanners, &mut rng),
+                    None => correlated_item(opts, &mut rng),
+                })
+                .collect::<Vec<_>>();
+            let total_weight: u64 = items.iter().map(|item| item.weight as u64).sum();
+            Problem {
+                id,
+                max_weight: (total_weight as f64 * opts.capacity_ratio).round() as u32,
+                size: opts.size,
+                min_cost: None,
+                items,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ioutils::{format_problem_line, parse_problem_line};
+
+    fn opts(args: &[&str]) -> GenerateOpts {
+        GenerateOpts::from_iter_safe(["generate", "--count=5", "--size=40"].iter().chain(args))
+            .unwrap()
+    }
+
+    fn items(args: &[&str]) -> Vec<Item> {
+        generate(&opts(args), 11)
+            .unwrap()
+            .into_iter()
+            .flat_map(|problem| problem.items)
+            .collect()
+    }
+
+    // small max cost used to make costs of corr and strong items zero or negative
+    const LIMITS: [(u32, u32); 3] = [(100, 250), (1000, 5), (5, 1000)];
+
+    #[test]
+    fn correlated_items_stay_in_range() {
+        for &(max_weight, max_cost) in &LIMITS {
+            let weight_arg = format!("--max-weight={}", max_weight);
+            let cost_arg = format!("--max-cost={}", max_cost);
+            let tenth = max_cost / 10;
+            for &correlation in &["uni", "corr", "strong"] {
+                let correlation_arg = format!("--correlation={}", correlation);
+                for item in items(&[&weight_arg, &cost_arg, &correlation_arg]) {
+                    assert!((1..=max_weight).contains(&item.weight));
+                    assert!(item.cost >= 1, "{} {:?}", correlation, item);
+                    let scaled = (item.weight * max_cost).div_ceil(max_weight);
+                    let range = match correlation {
+                        "uni" => 1..=max_cost,
+                        "corr" => scaled.saturating_sub(tenth)..=scaled + tenth,
+                        _ => scaled + tenth..=scaled + tenth,
+                    };
+                    assert!(range.contains(&item.cost), "{} {:?}", correlation, item);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn classes_stay_in_range() {
+        for &(max_weight, max_cost) in &LIMITS {
+            let weight_arg = format!("--max-weight={}", max_weight);
+            let cost_arg = format!("--max-cost={}", max_cost);
+            let (w, c) = (max_weight as f64, max_cost as f64);
+            for &class in &[
+                "inverse-strong",
+                "almost-strong",
+                "subset-sum",
+                "spanner",
+                "profit-ceiling",
+                "circle",
+            ] {
+                let class_arg = format!("--class={}", class);
+                for item in items(&[&weight_arg, &cost_arg, &class_arg]) {
+                    assert!(item.weight >= 1 && item.cost >= 1, "{} {:?}", class, item);
+                    let (weight, cost) = (item.weight as f64, item.cost as f64);
+                    let in_range = match class {
+                        "inverse-strong" => {
+                            let scaled = cost * w / c + w / 10.0;
+                            cost <= c && weight >= scaled && weight < scaled + 1.0
+                        }
+                        "almost-strong" => {
+                            let base = weight * c / w + c / 10.0;
+                            weight <= w
+                                && cost >= base - c / 500.0 - 1.0
+                                && cost <= base + c / 500.0 + 1.0
+                        }
+                        "subset-sum" => weight <= w && item.cost == item.weight,
+                        // multiples of shrunk spanners stay about in range of correlated items
+                        "spanner" => weight <= 2.0 * w + SPANNER_MULTIPLIER as f64,
+                        "profit-ceiling" => {
+                            weight <= w && item.cost % 3 == 0 && item.cost - item.weight < 3
+                        }
+                        _ => weight <= w && cost <= 4.0 / 3.0 * w,
+                    };
+                    assert!(in_range, "{} {:?}", class, item);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn seed_and_capacity_ratio() {
+        let opts = opts(&["--capacity-ratio=0.3", "--correlation=corr"]);
+        let problems = generate(&opts, 5).unwrap();
+        let again = generate(&opts, 5).unwrap();
+        assert_eq!(problems.len(), 5);
+        problems
+            .iter()
+            .zip(again.iter())
+            .for_each(|(problem, other)| {
+                assert_eq!(problem.items, other.items);
+                assert_eq!(problem.size, 40);
+                let total = problem.items.iter().map(|x| x.weight).sum::<u32>() as f64;
+                assert!((problem.max_weight as f64 - 0.3 * total).abs() <= 0.5);
+            });
+        assert!(generate(&self::opts(&["--max-cost=0"]), 5).is_err());
+    }
+
+    #[test]
+    fn generated_lines_parse_back() {
+        for problem in generate(&opts(&["--class=spanner"]), 3).unwrap() {
+            let parsed = parse_problem_line(&format_problem_line(&problem)).unwrap();
+            assert_eq!(parsed.id, problem.id);
+            assert_eq!(parsed.max_weight, problem.max_weight);
+            assert_eq!(parsed.items, problem.items);
+        }
+    }
+}
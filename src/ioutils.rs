@@ -43,6 +43,28 @@ impl FromStr for ProblemFromfile {
     }
 }
 
+// number of bytes, optionally with binary suffix K, M or G (e.g. 512M)
+#[derive(Debug, Clone, Copy)]
+pub struct MemorySize(pub usize);
+
+impl FromStr for MemorySize {
+    type Err = DisplayError;
+    fn from_str(size: &str) -> Result<MemorySize, DisplayError> {
+        let (number, multiplier) = match size.chars().last() {
+            Some('K') | Some('k') => (&size[..size.len() - 1], 1 << 10),
+            Some('M') | Some('m') => (&size[..size.len() - 1], 1 << 20),
+            Some('G') | Some('g') => (&size[..size.len() - 1], 1 << 30),
+            _ => (size, 1),
+        };
+        number
+            .parse::<usize>()
+            .ok()
+            .and_then(|n| n.checked_mul(multiplier))
+            .map(MemorySize)
+            .ok_or_else(|| format!("Could not parse memory size {:?}", size).into())
+    }
+}
+
 pub fn next_parse_with_err<'a, T, K>(iter: &mut T) -> Result<K, DisplayError>
 where
     T: Iterator<Item = &'a str>,
@@ -79,7 +101,7 @@ pub fn parse_problem_line(line: &str) -> Result<Problem, DisplayError> {
         "Line was not exhausted, wrong problem line!"
     );
     Ok(Problem {
-        id: id.abs() as u32,
+        id: id.unsigned_abs(),
         max_weight,
         size,
         min_cost,
@@ -87,6 +109,20 @@ pub fn parse_problem_line(line: &str) -> Result<Problem, DisplayError> {
     })
 }
 
+// inverse of parse_problem_line
+pub fn format_problem_line(problem: &Problem) -> String {
+    let head = match problem.min_cost {
+        Some(min_cost) => format!(
+            "-{} {} {} {}",
+            problem.id, problem.size, problem.max_weight, min_cost
+        ),
+        None => format!("{} {} {}", problem.id, problem.size, problem.max_weight),
+    };
+    problem.items.iter().fold(head, |line, item| {
+        format!("{} {} {}", line, item.weight, item.cost)
+    })
+}
+
 pub fn parse_solution_line(line: &str) -> Result<Solution, DisplayError> {
     let mut iter = line.split(' ').filter(|x| !x.is_empty());
     let id = next_parse_with_err(&mut iter)?;
@@ -94,16 +130,14 @@ pub fn parse_solution_line(line: &str) -> Result<Solution, DisplayError> {
     let cost = next_parse_with_err(&mut iter)?;
     let items = Some(
         (0..size)
-            .map(
-                |_| match iter.next().ok_or_else(|| "Not enough bits in line!")? {
-                    "1" => Ok(true),
-                    "0" => Ok(false),
-                    _ => Err("Reference solution is not in (0, 1)!".into()),
-                },
-            )
+            .map(|_| match iter.next().ok_or("Not enough bits in line!")? {
+                "1" => Ok(true),
+                "0" => Ok(false),
+                _ => Err("Reference solution is not in (0, 1)!".into()),
+            })
             .collect::<Result<Vec<_>, DisplayError>>()?,
     );
-    if iter.next() != None {
+    if iter.next().is_some() {
         return Err("Line was not exhausted, wrong solution line!".into());
     }
     Ok(Solution {
@@ -113,3 +147,24 @@ pub fn parse_solution_line(line: &str) -> Result<Solution, DisplayError> {
         items,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_size_suffixes() {
+        assert_eq!(MemorySize::from_str("1000").unwrap().0, 1000);
+        assert_eq!(MemorySize::from_str("2k").unwrap().0, 2048);
+        assert_eq!(MemorySize::from_str("512M").unwrap().0, 512 << 20);
+        assert_eq!(MemorySize::from_str("3G").unwrap().0, 3 << 30);
+    }
+
+    #[test]
+    fn invalid_memory_size() {
+        assert!(MemorySize::from_str("").is_err());
+        assert!(MemorySize::from_str("M").is_err());
+        assert!(MemorySize::from_str("12T").is_err());
+        assert!(MemorySize::from_str(&format!("{}G", usize::MAX)).is_err());
+    }
+}
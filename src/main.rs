@@ -9,64 +9,172 @@ use std::time::{Duration, Instant};
 use derive_more::Display;
 use structopt::clap::{Error, ErrorKind};
 
+mod analyze;
+mod bench;
+mod generator;
 mod ioutils;
+mod plot;
 mod solvers;
+use analyze::{analyze, AnalyzeOpts};
+use bench::{bench, write_bench, BenchOpts};
+use generator::{generate, GenerateOpts};
 use ioutils::*;
-use solvers::{utils::calculate_practical_ftpas_error, *};
+use plot::{plot, PlotOpts};
+use solvers::{
+    utils::{calculate_practical_ftpas_error, calculate_theoretical_ftpas_error},
+    *,
+};
 
 fn main() -> Result<(), Error> {
-    let opts = Opts::from_args();
+    let mut opts = Opts::from_args();
+    // random seed is drawn once for every subcommand, so it can be printed and run
+    // replayed by --seed
+    let seed = *opts.seed.get_or_insert_with(rand::random);
+
+    match opts.command.take() {
+        Some(Command::Features { input_task }) => {
+            for problem in &input_task.0 {
+                println!("{} {}", problem.id, features::features(problem));
+            }
+            Ok(())
+        }
+        Some(Command::Generate(generate_opts)) => {
+            let seed = generate_opts.seed.unwrap_or(seed);
+            // stdout is the instance file
+            eprintln!("seed: {}", seed);
+            let problems = generate(&generate_opts, seed)
+                .map_err(|e| Error::with_description(&e.0, ErrorKind::ValueValidation))?;
+            for problem in &problems {
+                println!("{}", format_problem_line(problem));
+            }
+            Ok(())
+        }
+        Some(Command::Bench(bench_opts)) => bench(&opts, &bench_opts)
+            .and_then(|data| write_bench(&bench_opts.output, &data))
+            .map_err(|e| Error::with_description(&e.0, ErrorKind::ArgumentConflict)),
+        Some(Command::Plot(plot_opts)) => {
+            plot(&plot_opts).map_err(|e| Error::with_description(&e.0, ErrorKind::ArgumentConflict))
+        }
+        Some(Command::AnalyzeApprox(analyze_opts)) => analyze(&opts, &analyze_opts)
+            .map_err(|e| Error::with_description(&e.0, ErrorKind::ArgumentConflict)),
+        None => solve(opts, seed),
+    }
+}
+
+fn solve(mut opts: Opts, seed: u64) -> Result<(), Error> {
+    let input = match (&opts.method, opts.input_task.take()) {
+        (Some(_), Some(input)) => input,
+        _ => {
+            return Err(Error::with_description(
+                "Missing method and input file or subcommand.",
+                ErrorKind::MissingRequiredArgument,
+            ))
+        }
+    };
 
     let solver = Solver::from_opts(&opts)
         .map_err(|e| Error::with_description(&e.0, ErrorKind::ArgumentConflict))?;
 
-    let input = &opts.input_task;
+    if solver.is_randomized() {
+        println!("seed: {}", seed);
+    }
 
-    let ref_solutions = if let Some(ref sol) = opts.solution {
-        Some(sol.0.iter().fold(HashMap::new(), |mut map, value| {
+    // exceeding memory limit is checked before any problem is solved
+    let fallback = Solver::memory_fallback(&opts);
+    let solvers = input
+        .0
+        .iter()
+        .map(|problem| solver.within_memory_limit(&fallback, problem, &opts))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| Error::with_description(&e.0, ErrorKind::ValueValidation))?;
+
+    let ref_solutions = opts.solution.as_ref().map(|sol| {
+        sol.0.iter().fold(HashMap::new(), |mut map, value| {
             map.insert(value.id, value);
             map
-        }))
-    } else {
-        None
-    };
+        })
+    });
 
     let mut stats = Stats::default();
 
     let durations = input
         .0
         .iter()
-        .map(|problem| {
+        .zip(solvers)
+        .map(|(problem, (solver, fell_back))| {
             let start = Instant::now();
-            (
-                match problem.min_cost.is_none() || opts.force_construction {
-                    true => solver.construction(&problem),
-                    false => solver.decision(&problem),
-                },
-                start.elapsed(),
-                problem,
-            )
-        })
-        .map(|(solution, elapsed, problem)| {
-            let mut output = String::new();
-            output += format!("{} {} {}", solution.id, solution.size, solution.cost).as_str();
-            if let Some(items) = &solution.items {
-                output += items
-                    .iter()
-                    .map(|&i| if i { " 1" } else { " 0" })
-                    .join("")
-                    .as_str();
+            let reduction = if opts.reduce {
+                Some(reduce(problem))
+            } else {
+                None
+            };
+            let reduced = reduction.as_ref().map_or(problem, |r| &r.problem);
+            let solution = match problem.min_cost.is_none() || opts.force_construction {
+                true => solver.construction(reduced),
+                false => solver.decision(reduced),
+            };
+            let solution = match &reduction {
+                Some(reduction) => reduction.restore(solution),
+                None => solution,
+            };
+            let solution = if opts.polish {
+                polish(problem, solution)
+            } else {
+                solution
+            };
+            let elapsed = start.elapsed();
+            // printed after time, separated by semicolons
+            let mut notes = Vec::new();
+            if fell_back {
+                notes.push("memory limit fallback: pruning".to_string());
             }
-            let mut additional_info = "".to_string();
-            if ref_solutions.is_some() && (problem.min_cost.is_none() || opts.force_construction) {
-                let reference = ref_solutions.as_ref().unwrap().get(&solution.id).unwrap();
-                additional_info +=
-                    &check_solution(reference, &solution, problem, &solver, &opts, &mut stats);
+            if let Some(reduction) = &reduction {
+                notes.push(format!(
+                    "fixed by reduction: {}/{}",
+                    reduction.fixed_count(),
+                    problem.size
+                ));
             }
-            println!("time: {:?} {}\n{}", elapsed, additional_info, output);
-
-            elapsed
+            if let Portfolio(portfolio) = solver {
+                // no winner, when no method returned solution
+                if let Some(winner) = portfolio.last_winner() {
+                    notes.push(format!("portfolio winner: {}", winner));
+                }
+            }
+            if let Auto(auto) = solver {
+                notes.push(format!("auto: {}", auto.last_chosen().unwrap()));
+            }
+            (solution, elapsed, problem, reduction, solver, notes)
         })
+        .map(
+            |(solution, elapsed, problem, reduction, used_solver, mut notes)| {
+                let mut output = String::new();
+                output += format!("{} {} {}", solution.id, solution.size, solution.cost).as_str();
+                if let Some(items) = &solution.items {
+                    output += items
+                        .iter()
+                        .map(|&i| if i { " 1" } else { " 0" })
+                        .join("")
+                        .as_str();
+                }
+                if let Some(ref_solutions) = &ref_solutions {
+                    if problem.min_cost.is_none() || opts.force_construction {
+                        let reference = ref_solutions.get(&solution.id).unwrap();
+                        let solved = reduction.as_ref().map_or(problem, |r| &r.problem);
+                        notes.extend(check_solution(
+                            reference,
+                            &solution,
+                            solved,
+                            used_solver,
+                            &mut stats,
+                        ));
+                    }
+                }
+                println!("time: {:?} {}\n{}", elapsed, notes.join("; "), output);
+
+                elapsed
+            },
+        )
         .collect::<Vec<_>>();
 
     let max_time = durations.iter().max().cloned().unwrap();
@@ -103,18 +211,18 @@ struct Stats {
     no_solution: usize,
 }
 
+// solved is the problem solver got (reduced with --reduce), ftpas error bounds are computed on it
 fn check_solution(
     reference: &Solution,
     solution: &Solution,
-    problem: &Problem,
+    solved: &Problem,
     solver: &Solver,
-    opts: &Opts,
     stats: &mut Stats,
-) -> String {
+) -> Option<String> {
     stats.instances += 1;
-    if !solution.items.is_some(){
+    if solution.items.is_none() {
         stats.no_solution += 1;
-        " No solution found".to_string()
+        Some("No solution found".to_string())
     } else if solver.is_exact() {
         if *reference != *solution
             && reference.cost == solution.cost
@@ -124,7 +232,7 @@ fn check_solution(
         } else {
             assert_eq!(reference, solution);
         }
-        "".to_string()
+        None
     } else {
         let absolute_error = reference.cost - solution.cost;
         let ref_cost = reference.cost as f64;
@@ -134,22 +242,23 @@ fn check_solution(
         stats.relative_error_max = stats.relative_error_max.max(relative_error);
         stats.relative_error_total += relative_error;
 
-        if let FTPAS(_) = solver {
-            let gcd = opts.precision.unwrap();
-            let practical_error = calculate_practical_ftpas_error(&problem, gcd);
+        if let FTPAS(ftpas) = solver {
+            let gcd = ftpas.divisor(solved);
+            let practical_error = calculate_practical_ftpas_error(solved, gcd);
 
-            format!(
-                " errors: ratio: {} absolute: {} max possible: {} ratio: {}",
+            Some(format!(
+                "errors: ratio: {} absolute: {} max possible: {} ratio: {} theoretical: {}",
                 relative_error,
                 absolute_error,
                 practical_error,
-                absolute_error as f32 / practical_error as f32
-            )
+                absolute_error as f32 / practical_error as f32,
+                calculate_theoretical_ftpas_error(solved, gcd)
+            ))
         } else {
-            format!(
+            Some(format!(
                 "errors: ratio: {} absolute: {}",
                 relative_error, absolute_error
-            )
+            ))
         }
     }
 }
@@ -173,17 +282,117 @@ impl std::convert::From<String> for DisplayError {
 #[derive(StructOpt, Debug)]
 #[structopt(name = "knapsack", author = "Martin Quarda <martin@quarda.cz>")]
 pub struct Opts {
-    method: Methods,
-    input_task: ProblemFromfile,
+    // method and input are required without subcommand
+    method: Option<Methods>,
+    input_task: Option<ProblemFromfile>,
     solution: Option<SolutionsFromFile>,
+    #[structopt(subcommand)]
+    command: Option<Command>,
     #[structopt(long)]
     precision: Option<u32>,
     #[structopt(long)]
+    epsilon: Option<f64>,
+    #[structopt(long)]
     force_construction: bool,
+    // improves found solution by local search
+    #[structopt(long)]
+    polish: bool,
+    // fixes items by bounds before solving
+    #[structopt(long)]
+    reduce: bool,
+    // upper bound of branch and bound solvers
+    #[structopt(long, default_value = "dantzig")]
+    bound: UpperBound,
+    // exact methods raced by portfolio, which support cancellation
+    #[structopt(
+        long,
+        use_delimiter = true,
+        default_value = "pruning,dynamic-weight,dynamic-cost"
+    )]
+    portfolio: Vec<Methods>,
+    // rules of auto method tried in order, [feature<value:|feature>value:]method
+    #[structopt(
+        long,
+        use_delimiter = true,
+        default_value = DEFAULT_RULES
+    )]
+    auto_rules: Vec<AutoRule>,
+    // threads of parallel solvers, all cores by default
+    #[structopt(long)]
+    threads: Option<usize>,
+    #[structopt(long)]
+    beam_width: Option<usize>,
+    #[structopt(long)]
+    discrepancies: Option<usize>,
     #[structopt(long)]
     memory_size: Option<usize>,
     #[structopt(long)]
     iterations: Option<usize>,
+    // initial state of tabu search
+    #[structopt(long, default_value = "greedy")]
+    initial: InitialState,
+    #[structopt(long, default_value = "random")]
+    restart: Restart,
+    #[structopt(long)]
+    restart_after: Option<usize>,
+    // initial temperature of simulated annealing
+    #[structopt(long)]
+    temperature: Option<f64>,
+    #[structopt(long, default_value = "geometric")]
+    cooling: Cooling,
+    #[structopt(long, default_value = "0.995")]
+    cooling_factor: f64,
+    #[structopt(long, default_value = "repair")]
+    overweight: Overweight,
+    #[structopt(long)]
+    population: Option<usize>,
+    #[structopt(long)]
+    generations: Option<usize>,
+    #[structopt(long)]
+    mutation_rate: Option<f64>,
+    #[structopt(long, default_value = "uniform")]
+    crossover: Crossover,
+    #[structopt(long, default_value = "1")]
+    elitism: usize,
+    #[structopt(long, default_value = "2")]
+    tournament_size: usize,
+    #[structopt(long)]
+    colony_size: Option<usize>,
+    #[structopt(long, default_value = "0.1")]
+    evaporation: f64,
+    #[structopt(long, default_value = "1")]
+    alpha: f64,
+    #[structopt(long, default_value = "2")]
+    beta: f64,
+    // restricted candidate list size of GRASP
+    #[structopt(long, default_value = "3")]
+    rcl_size: usize,
+    // random flips between local searches of iterated local search
+    #[structopt(long, default_value = "3")]
+    perturbation: usize,
+    // local search of GRASP and iterated local search tries also 2-2 exchanges, O(n^4) per step
+    #[structopt(long)]
+    double_swap: bool,
+    #[structopt(long)]
+    seed: Option<u64>,
+    #[structopt(long)]
+    memory_limit: Option<MemorySize>,
+    #[structopt(long)]
+    memory_fallback: bool,
+}
+
+#[derive(StructOpt, Debug)]
+pub enum Command {
+    // prints features of every problem, which decide speed of exact methods
+    Features { input_task: ProblemFromfile },
+    // prints generated instances in the input format
+    Generate(GenerateOpts),
+    // measures methods on instance files and writes times as json for graph.py
+    Bench(BenchOpts),
+    // renders json of bench as svg charts like graph.py
+    Plot(PlotOpts),
+    // errors of ftpas or approx-pruning against reference solutions for range of precisions
+    AnalyzeApprox(AnalyzeOpts),
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -196,7 +405,7 @@ use solvers::ratio;
 
 impl Item {
     fn cost_weight_ratio(&self) -> ratio {
-        return ratio::new_raw(self.cost, self.weight);
+        ratio::new_raw(self.cost, self.weight)
     }
 }
 
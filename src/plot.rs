@@ -0,0 +1,484 @@
+use super::{
+    bench::{read_bench, BenchData},
+    DisplayError,
+};
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+pub struct PlotOpts {
+    // json written by bench
+    #[structopt(default_value = "data.json")]
+    input: PathBuf,
+    // directory, where svg files are written
+    #[structopt(long, default_value = ".")]
+    output: PathBuf,
+    #[structopt(long, default_value = "velikost")]
+    xlabel: String,
+    // histogram of problem times of every set, size and method
+    #[structopt(long)]
+    histograms: bool,
+    #[structopt(long, default_value = "30")]
+    bins: usize,
+}
+
+const WIDTH: f64 = 1200.0;
+const HEIGHT: f64 = 800.0;
+const LEFT: f64 = 90.0;
+const RIGHT: f64 = 30.0;
+const TOP: f64 = 50.0;
+const BOTTOM: f64 = 70.0;
+
+// colours of graph.py, other methods take colours from palette
+const COLORS: [(&str, &str); 4] = [
+    ("naive", "red"),
+    ("pruning", "purple"),
+    ("dynamic-cost", "green"),
+    ("dynamic-weight", "blue"),
+];
+const PALETTE: [&str; 8] = [
+    "orange", "brown", "teal", "olive", "magenta", "gray", "navy", "crimson",
+];
+
+// (name, dash pattern) of plotted statistics, avg solid and max dotted
+const LINES: [(&str, &str); 2] = [("avg", "none"), ("max", "2,5")];
+
+fn color(method: &str, others: &mut usize) -> &'static str {
+    match COLORS.iter().find(|(name, _)| *name == method) {
+        Some((_, color)) => color,
+        None => {
+            *others += 1;
+            PALETTE[(*others - 1) % PALETTE.len()]
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+// linear mapping of value from range to pixels
+fn scale(value: f64, (min, max): (f64, f64), (from, to): (f64, f64)) -> f64 {
+    from + (value - min) / (max - min) * (to - from)
+}
+
+// range of values, widened when all values are same
+fn range(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), x| {
+        (min.min(x), max.max(x))
+    });
+    if min < max {
+        (min, max)
+    } else {
+        (min - 1.0, min + 1.0)
+    }
+}
+
+// frame with title, axis labels and ticks as (pixel, label) pairs
+fn frame(
+    svg: &mut String,
+    title: &str,
+    xlabel: &str,
+    ylabel: &str,
+    xticks: &[(f64, String)],
+    yticks: &[(f64, String)],
+) {
+    let (right, bottom) = (WIDTH - RIGHT, HEIGHT - BOTTOM);
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="14">"#,
+        w = WIDTH,
+        h = HEIGHT
+    )
+    .unwrap();
+    writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#).unwrap();
+    writeln!(
+        svg,
+        r#"<text x="{}" y="{}" text-anchor="middle" font-size="18">{}</text>"#,
+        (LEFT + right) / 2.0,
+        TOP / 2.0 + 6.0,
+        escape(title)
+    )
+    .unwrap();
+    for (x, label) in xticks {
+        writeln!(
+            svg,
+            r#"<line x1="{x}" y1="{TOP}" x2="{x}" y2="{bottom}" stroke="lightgray" stroke-dasharray="6,3,1,3"/>"#
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}" text-anchor="middle">{}</text>"#,
+            x,
+            bottom + 20.0,
+            escape(label)
+        )
+        .unwrap();
+    }
+    for (y, label) in yticks {
+        writeln!(
+            svg,
+            r#"<line x1="{LEFT}" y1="{y}" x2="{right}" y2="{y}" stroke="lightgray" stroke-dasharray="6,3,1,3"/>"#
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}" text-anchor="end">{}</text>"#,
+            LEFT - 8.0,
+            y + 5.0,
+            escape(label)
+        )
+        .unwrap();
+    }
+    writeln!(
+        svg,
+        r#"<rect x="{LEFT}" y="{TOP}" width="{}" height="{}" fill="none" stroke="black"/>"#,
+        right - LEFT,
+        bottom - TOP
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r#"<text x="{}" y="{}" text-anchor="middle">{}</text>"#,
+        (LEFT + right) / 2.0,
+        HEIGHT - 20.0,
+        escape(xlabel)
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r#"<text x="20" y="{y}" text-anchor="middle" transform="rotate(-90 20 {y})">{}</text>"#,
+        escape(ylabel),
+        y = (TOP + bottom) / 2.0
+    )
+    .unwrap();
+}
+
+// time of every method against size in log scale, like graph.py,
+// None when no size is number with positive time
+pub fn size_chart(set: &str, sizes: &BenchData, xlabel: &str) -> Option<String> {
+    let data = &sizes[set];
+    // sizes are numbers, invalid ones are skipped
+    let points = |method: &str, line: &str| {
+        let mut points = data
+            .iter()
+            .filter_map(|(size, methods)| {
+                let stats = methods.get(method)?;
+                let time = if line == "avg" { stats.avg } else { stats.max };
+                Some((size.parse::<f64>().ok()?, time)).filter(|_| time > 0.0)
+            })
+            .collect::<Vec<_>>();
+        points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        points
+    };
+    let mut methods = data
+        .values()
+        .flat_map(|methods| methods.keys().cloned())
+        .collect::<Vec<_>>();
+    methods.sort();
+    methods.dedup();
+    let all = methods
+        .iter()
+        .flat_map(|method| LINES.iter().flat_map(move |(line, _)| points(method, line)))
+        .collect::<Vec<_>>();
+    if all.is_empty() {
+        return None;
+    }
+
+    let xrange = range(all.iter().map(|point| point.0));
+    let (ymin, ymax) = range(all.iter().map(|point| point.1.log10()));
+    let yrange = (ymin.floor(), ymax.ceil());
+    let (xpixels, ypixels) = ((LEFT, WIDTH - RIGHT), (HEIGHT - BOTTOM, TOP));
+
+    let mut xs = all.iter().map(|point| point.0).collect::<Vec<_>>();
+    xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    xs.dedup();
+    let xticks = xs
+        .iter()
+        .map(|&x| (scale(x, xrange, xpixels), x.to_string()))
+        .collect::<Vec<_>>();
+    let yticks = (yrange.0 as i32..=yrange.1 as i32)
+        .map(|e| (scale(e as f64, yrange, ypixels), format!("1e{}", e)))
+        .collect::<Vec<_>>();
+
+    let mut svg = String::new();
+    frame(
+        &mut svg,
+        &format!("Sada {}", set),
+        xlabel,
+        "čas [s]",
+        &xticks,
+        &yticks,
+    );
+    let mut others = 0;
+    for (i, method) in methods.iter().enumerate() {
+        let color = color(method, &mut others);
+        for (line, dash) in &LINES {
+            let pixels = points(method, line)
+                .iter()
+                .map(|&(x, time)| {
+                    (
+                        scale(x, xrange, xpixels),
+                        scale(time.log10(), yrange, ypixels),
+                    )
+                })
+                .collect::<Vec<_>>();
+            let polyline = pixels
+                .iter()
+                .map(|(x, y)| format!("{:.1},{:.1}", x, y))
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(
+                svg,
+                r#"<polyline points="{polyline}" fill="none" stroke="{color}" stroke-width="2" stroke-dasharray="{dash}"/>"#
+            )
+            .unwrap();
+            for (x, y) in pixels {
+                writeln!(
+                    svg,
+                    r#"<circle cx="{x:.1}" cy="{y:.1}" r="3" fill="{color}"/>"#
+                )
+                .unwrap();
+            }
+        }
+        // legend in top left corner
+        let y = TOP + 20.0 + 20.0 * i as f64;
+        writeln!(
+            svg,
+            r#"<line x1="{}" y1="{y}" x2="{}" y2="{y}" stroke="{color}" stroke-width="2"/>"#,
+            LEFT + 10.0,
+            LEFT + 40.0
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}">{}</text>"#,
+            LEFT + 48.0,
+            y + 5.0,
+            escape(method)
+        )
+        .unwrap();
+    }
+    for (i, (line, dash)) in LINES.iter().enumerate() {
+        let y = TOP + 20.0 + 20.0 * (methods.len() + i) as f64;
+        writeln!(
+            svg,
+            r#"<line x1="{}" y1="{y}" x2="{}" y2="{y}" stroke="black" stroke-width="2" stroke-dasharray="{dash}"/>"#,
+            LEFT + 10.0,
+            LEFT + 40.0
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}">{}</text>"#,
+            LEFT + 48.0,
+            y + 5.0,
+            line
+        )
+        .unwrap();
+    }
+    svg += "</svg>\n";
+    Some(svg)
+}
+
+// counts of problem times in bins, like plot.py
+pub fn histogram(title: &str, times: &[f64], bins: usize) -> String {
+    let bins = bins.max(1);
+    let xrange = range(times.iter().cloned());
+    let mut counts = vec![0usize; bins];
+    for &time in times {
+        let bin = ((time - xrange.0) / (xrange.1 - xrange.0) * bins as f64) as usize;
+        counts[bin.min(bins - 1)] += 1;
+    }
+    let max_count = counts.iter().cloned().max().unwrap_or(0).max(1);
+    let step = max_count.div_ceil(10);
+    let yrange = (0.0, (max_count.div_ceil(step) * step) as f64);
+    let (xpixels, ypixels) = ((LEFT, WIDTH - RIGHT), (HEIGHT - BOTTOM, TOP));
+
+    let xticks = (0..=5)
+        .map(|i| {
+            let x = xrange.0 + (xrange.1 - xrange.0) * i as f64 / 5.0;
+            (scale(x, xrange, xpixels), format!("{:.2e}", x))
+        })
+        .collect::<Vec<_>>();
+    let yticks = (0..=yrange.1 as usize)
+        .step_by(step)
+        .map(|count| (scale(count as f64, yrange, ypixels), count.to_string()))
+        .collect::<Vec<_>>();
+
+    let mut svg = String::new();
+    frame(&mut svg, title, "čas [s]", "počet", &xticks, &yticks);
+    let bin_width = (xpixels.1 - xpixels.0) / bins as f64;
+    for (i, &count) in counts.iter().enumerate() {
+        let top = scale(count as f64, yrange, ypixels);
+        writeln!(
+            svg,
+            r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="steelblue" stroke="white"/>"#,
+            xpixels.0 + bin_width * i as f64,
+            top,
+            bin_width,
+            ypixels.0 - top
+        )
+        .unwrap();
+    }
+    svg += "</svg>\n";
+    svg
+}
+
+fn write_svg(dir: &Path, name: &str, svg: &str) -> Result<(), DisplayError> {
+    let path = dir.join(format!("{}.svg", name));
+    fs::write(&path, svg)
+        .map_err(|e| format!("Could not write plot: {:?}, because: {}", path, e).into())
+}
+
+pub fn plot(opts: &PlotOpts) -> Result<(), DisplayError> {
+    let data = read_bench(&opts.input)?;
+    for set in data.keys() {
+        match size_chart(set, &data, &opts.xlabel) {
+            Some(svg) => write_svg(&opts.output, set, &svg)?,
+            None => eprintln!("Set {} has no numeric sizes, its chart is skipped.", set),
+        }
+    }
+    if opts.histograms {
+        for (set, sizes) in &data {
+            for (size, methods) in sizes {
+                for (method, stats) in methods.iter().filter(|(_, s)| !s.times.is_empty()) {
+                    let title = format!("Histogram sady {}{} algoritmem {}", set, size, method);
+                    let svg = histogram(&title, &stats.times, opts.bins);
+                    write_svg(&opts.output, &format!("{}{}_{}", set, size, method), &svg)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::bench::{write_bench, BenchStats};
+    use super::*;
+
+    fn stats(avg: f64, max: f64, times: &[f64]) -> BenchStats {
+        BenchStats {
+            avg,
+            max,
+            runs: 1,
+            times: times.to_vec(),
+            ..BenchStats::default()
+        }
+    }
+
+    // set corr with pruning and greedy on sizes 20 and 40, and pruning on invalid size
+    fn data() -> BenchData {
+        let mut data = BenchData::new();
+        let sizes = data.entry("corr".to_string()).or_default();
+        for (size, scale) in &[("20", 1.0), ("40", 10.0)] {
+            let methods = sizes.entry(size.to_string()).or_default();
+            methods.insert("pruning".into(), stats(1e-3 * scale, 2e-3 * scale, &[1e-3]));
+            methods.insert("greedy".into(), stats(1e-5 * scale, 3e-5 * scale, &[]));
+        }
+        sizes
+            .entry("x".to_string())
+            .or_default()
+            .insert("pruning".into(), stats(1.0, 1.0, &[]));
+        data
+    }
+
+    fn count(svg: &str, pattern: &str) -> usize {
+        svg.matches(pattern).count()
+    }
+
+    #[test]
+    fn size_chart_has_line_of_every_method() {
+        let svg = size_chart("corr", &data(), "velikost").unwrap();
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>\n"));
+        assert_eq!(count(&svg, "<svg"), 1);
+        assert!(!svg.contains("NaN") && !svg.contains("inf"));
+        // avg and max of two methods, each with points of two valid sizes
+        assert_eq!(count(&svg, "<polyline"), 4);
+        assert_eq!(count(&svg, "<circle"), 8);
+        assert!(svg.contains(r#"stroke="purple""#));
+        assert!(svg.contains(r#"stroke="orange""#));
+        assert!(svg.contains("Sada corr"));
+        // times from 1e-5 to 2e-2 get decades from 1e-5 to 1e-1
+        assert!(svg.contains(">1e-5<") && svg.contains(">1e-1<"));
+        assert!(!svg.contains(">1e-6<") && !svg.contains(">1e0<"));
+    }
+
+    #[test]
+    fn texts_are_escaped() {
+        let mut data = BenchData::new();
+        data.entry("a&b".to_string())
+            .or_default()
+            .entry("10".to_string())
+            .or_default()
+            .insert("pruning".into(), stats(1.0, 1.0, &[]));
+        let svg = size_chart("a&b", &data, "<n>").unwrap();
+        assert!(svg.contains("Sada a&amp;b") && svg.contains("&lt;n&gt;"));
+        assert!(!svg.contains("a&b"));
+    }
+
+    #[test]
+    fn set_without_numeric_sizes_has_no_chart() {
+        let mut data = data();
+        let sizes = data.entry("text".to_string()).or_default();
+        sizes
+            .entry("x".to_string())
+            .or_default()
+            .insert("pruning".into(), stats(1.0, 1.0, &[]));
+        sizes
+            .entry("10".to_string())
+            .or_default()
+            .insert("pruning".into(), stats(0.0, 0.0, &[]));
+        assert!(size_chart("text", &data, "velikost").is_none());
+    }
+
+    #[test]
+    fn histogram_counts_times_in_bins() {
+        let svg = histogram("title", &[1.0, 2.0, 2.0, 3.0, 10.0], 3);
+        assert_eq!(count(&svg, r#"fill="steelblue""#), 3);
+        // highest bin with 4 times reaches top of chart, empty bin has no height
+        assert!(svg.contains(&format!(r#"y="{:.1}""#, TOP)));
+        assert!(svg.contains(r#"height="0.0""#));
+        let same = histogram("same", &[2.0, 2.0], 0);
+        assert_eq!(count(&same, r#"fill="steelblue""#), 1);
+        assert!(!same.contains("NaN"));
+    }
+
+    #[test]
+    fn plot_writes_charts_and_histograms() {
+        let dir = std::env::temp_dir().join(format!("knapsack-plot-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("data.json");
+        write_bench(&input, &data()).unwrap();
+        let opts = PlotOpts::from_iter_safe(&[
+            "plot",
+            input.to_str().unwrap(),
+            "--output",
+            dir.to_str().unwrap(),
+            "--histograms",
+        ])
+        .unwrap();
+        plot(&opts).unwrap();
+        let mut files = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        files.sort();
+        fs::remove_dir_all(&dir).unwrap();
+        // greedy has no times, so it has no histograms
+        assert_eq!(
+            files,
+            vec![
+                "corr.svg",
+                "corr20_pruning.svg",
+                "corr40_pruning.svg",
+                "data.json"
+            ]
+        );
+    }
+}
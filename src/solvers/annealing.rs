@@ -0,0 +1,211 @@
+use super::{
+    sort_by_cost_weight_ratio, utils::seeded_rng, DisplayError, Item, Problem, Solution,
+    SolverTrait,
+};
+
+use rand::Rng;
+use std::str::FromStr;
+
+#[derive(Debug, Clone)]
+pub struct SimulatedAnnealingSolver {
+    pub iterations: usize,
+    // when not set, maximal cost of item is used
+    pub temperature: Option<f64>,
+    pub cooling: Cooling,
+    pub cooling_factor: f64,
+    pub overweight: Overweight,
+    pub seed: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Cooling {
+    // temperature is multiplied by cooling factor
+    Geometric,
+    // temperature decreases to zero at last iteration
+    Linear,
+    // geometric, but after iterations / 10 without improvement temperature is raised
+    // back to half of previous reheat temperature
+    Reheating,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Overweight {
+    // overweight states are allowed, every unit of overweight costs as much as best ratio
+    Penalty,
+    // items with worst ratio are removed until state fits
+    Repair,
+}
+
+impl FromStr for Cooling {
+    type Err = DisplayError;
+    fn from_str(name: &str) -> Result<Cooling, DisplayError> {
+        match name {
+            "geometric" => Ok(Cooling::Geometric),
+            "linear" => Ok(Cooling::Linear),
+            "reheating" => Ok(Cooling::Reheating),
+            _ => Err(format!(
+                "Cooling {:?} not found, following are valid: geometric, linear, reheating.",
+                name
+            )
+            .into()),
+        }
+    }
+}
+
+impl FromStr for Overweight {
+    type Err = DisplayError;
+    fn from_str(name: &str) -> Result<Overweight, DisplayError> {
+        match name {
+            "penalty" => Ok(Overweight::Penalty),
+            "repair" => Ok(Overweight::Repair),
+            _ => Err(format!(
+                "Overweight handling {:?} not found, following are valid: penalty, repair.",
+                name
+            )
+            .into()),
+        }
+    }
+}
+
+// flips item in state, returns new (cost, weight)
+fn flip(state: &mut [bool], items: &[Item], index: usize, cost: u32, weight: u32) -> (u32, u32) {
+    state[index] = !state[index];
+    if state[index] {
+        (cost + items[index].cost, weight + items[index].weight)
+    } else {
+        (cost - items[index].cost, weight - items[index].weight)
+    }
+}
+
+impl SolverTrait for SimulatedAnnealingSolver {
+    fn construction(&self, problem: &Problem) -> Solution {
+        let (items, mapping) = sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
+
+        if items.is_empty() {
+            return Solution::empty(problem.id, problem.size);
+        }
+
+        let mut rng = seeded_rng(self.seed, problem);
+
+        let max_weight = problem.max_weight;
+        // items are sorted, so first has best ratio
+        let penalty = items[0].cost as f64 / items[0].weight.max(1) as f64;
+        let value = |cost: u32, weight: u32| {
+            cost as f64 - penalty * weight.saturating_sub(max_weight) as f64
+        };
+
+        let initial_temperature = self
+            .temperature
+            .unwrap_or_else(|| items.iter().map(|item| item.cost).max().unwrap() as f64);
+        let mut temperature = initial_temperature;
+        let mut reheat_temperature = initial_temperature;
+        let mut since_improvement = 0;
+
+        let mut state = vec![false; items.len()];
+        let (mut cost, mut weight) = (0, 0);
+        let mut best_solution = state.clone();
+        let mut best_cost = 0;
+
+        // removed items by repair, so rejected move can be reverted
+        let mut repaired = Vec::new();
+
+        for iteration in 0..self.iterations {
+            let index = rng.gen_range(0, items.len());
+            let (mut new_cost, mut new_weight) = flip(&mut state, &items, index, cost, weight);
+
+            repaired.clear();
+            if let Overweight::Repair = self.overweight {
+                // worst ratio items are at the end
+                for i in (0..items.len()).rev() {
+                    if new_weight <= max_weight {
+                        break;
+                    }
+                    if state[i] {
+                        let flipped = flip(&mut state, &items, i, new_cost, new_weight);
+                        new_cost = flipped.0;
+                        new_weight = flipped.1;
+                        repaired.push(i);
+                    }
+                }
+            }
+
+            let delta = value(new_cost, new_weight) - value(cost, weight);
+            if delta >= 0.0 || rng.gen::<f64>() < (delta / temperature).exp() {
+                cost = new_cost;
+                weight = new_weight;
+            } else {
+                // repair could remove just added item, so it's restored first
+                repaired.iter().for_each(|&i| state[i] = true);
+                state[index] = !state[index];
+            }
+
+            since_improvement += 1;
+            if weight <= max_weight && cost > best_cost {
+                best_cost = cost;
+                best_solution.copy_from_slice(&state);
+                since_improvement = 0;
+            }
+
+            temperature = match self.cooling {
+                Cooling::Geometric => temperature * self.cooling_factor,
+                Cooling::Linear => {
+                    initial_temperature * (1.0 - (iteration + 1) as f64 / self.iterations as f64)
+                }
+                Cooling::Reheating if since_improvement > self.iterations / 10 => {
+                    since_improvement = 0;
+                    reheat_temperature /= 2.0;
+                    reheat_temperature
+                }
+                Cooling::Reheating => temperature * self.cooling_factor,
+            }
+            .max(f64::MIN_POSITIVE);
+        }
+
+        Solution {
+            id: problem.id,
+            size: problem.size,
+            cost: best_cost,
+            items: Some(best_solution.into_iter().enumerate().fold(
+                vec![false; problem.size],
+                |mut acc, (i, x)| {
+                    acc[mapping[i]] = x;
+                    acc
+                },
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use super::*;
+
+    fn solver(cooling: Cooling, overweight: Overweight) -> SimulatedAnnealingSolver {
+        SimulatedAnnealingSolver {
+            iterations: 2000,
+            temperature: None,
+            cooling,
+            cooling_factor: 0.995,
+            overweight,
+            seed: 42,
+        }
+    }
+
+    #[test]
+    fn every_cooling_and_overweight_fits() {
+        for &cooling in &[Cooling::Geometric, Cooling::Linear, Cooling::Reheating] {
+            for &overweight in &[Overweight::Penalty, Overweight::Repair] {
+                assert_feasible(&solver(cooling, overweight));
+            }
+        }
+    }
+
+    #[test]
+    fn parse_options() {
+        assert!(matches!("linear".parse(), Ok(Cooling::Linear)));
+        assert!(matches!("repair".parse(), Ok(Overweight::Repair)));
+        assert!("quadratic".parse::<Cooling>().is_err());
+        assert!("ignore".parse::<Overweight>().is_err());
+    }
+}
@@ -0,0 +1,164 @@
+use super::{sort_by_cost_weight_ratio, utils::seeded_rng, Problem, Solution, SolverTrait};
+
+use rand::Rng;
+
+#[derive(Debug, Clone)]
+pub struct AntColonySolver {
+    pub colony_size: usize,
+    pub iterations: usize,
+    // part of pheromone, which evaporates after each iteration
+    pub evaporation: f64,
+    // weight of pheromone trail
+    pub alpha: f64,
+    // weight of cost/weight ratio heuristic
+    pub beta: f64,
+    pub seed: u64,
+}
+
+// pheromone never falls under this, so every item can still be picked
+const MIN_PHEROMONE: f64 = 0.01;
+
+impl SolverTrait for AntColonySolver {
+    fn construction(&self, problem: &Problem) -> Solution {
+        let (items, mapping) = sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
+
+        if items.is_empty() {
+            return Solution::empty(problem.id, problem.size);
+        }
+
+        let mut rng = seeded_rng(self.seed, problem);
+
+        let heuristic = items
+            .iter()
+            .map(|item| {
+                let ratio = item.cost_weight_ratio();
+                (*ratio.numer() as f64 / (*ratio.denom()).max(1) as f64).powf(self.beta)
+            })
+            .collect::<Vec<_>>();
+        let mut pheromone = vec![1.0f64; items.len()];
+
+        let mut best_solution = vec![false; items.len()];
+        let mut best_cost = 0;
+
+        let mut attraction = vec![0.0; items.len()];
+
+        for _ in 0..self.iterations {
+            attraction
+                .iter_mut()
+                .zip(pheromone.iter().zip(heuristic.iter()))
+                .for_each(|(a, (p, h))| *a = p.powf(self.alpha) * h);
+
+            // every ant walks until no other item fits
+            let (iteration_solution, iteration_cost) = (0..self.colony_size)
+                .map(|_| {
+                    let mut solution = vec![false; items.len()];
+                    let (mut cost, mut rem_weight) = (0, problem.max_weight);
+                    loop {
+                        let total: f64 = items
+                            .iter()
+                            .zip(solution.iter().zip(attraction.iter()))
+                            .filter(|(item, (&used, _))| !used && item.weight <= rem_weight)
+                            .map(|(_, (_, a))| a)
+                            .sum();
+                        if total <= 0.0 {
+                            break;
+                        }
+                        let mut roulette = rng.gen::<f64>() * total;
+                        let picked = items
+                            .iter()
+                            .zip(solution.iter().zip(attraction.iter()))
+                            .enumerate()
+                            .filter(|(_, (item, (&used, _)))| !used && item.weight <= rem_weight)
+                            .find(|(_, (_, (_, &a)))| {
+                                roulette -= a;
+                                roulette <= 0.0
+                            })
+                            .map(|(i, _)| i);
+                        // floating point error can skip the last candidate
+                        let picked = match picked {
+                            Some(i) => i,
+                            None => break,
+                        };
+                        solution[picked] = true;
+                        cost += items[picked].cost;
+                        rem_weight -= items[picked].weight;
+                    }
+                    (solution, cost)
+                })
+                .max_by_key(|(_, cost)| *cost)
+                .unwrap_or_else(|| (vec![false; items.len()], 0));
+
+            if iteration_cost > best_cost {
+                best_cost = iteration_cost;
+                best_solution.copy_from_slice(&iteration_solution);
+            }
+
+            // iteration best deposits pheromone relative to global best
+            let deposit = iteration_cost as f64 / best_cost.max(1) as f64;
+            pheromone
+                .iter_mut()
+                .zip(iteration_solution.iter())
+                .for_each(|(p, &used)| {
+                    *p = (*p * (1.0 - self.evaporation)
+                        + if used {
+                            self.evaporation * deposit
+                        } else {
+                            0.0
+                        })
+                    .max(MIN_PHEROMONE)
+                });
+        }
+
+        Solution {
+            id: problem.id,
+            size: problem.size,
+            cost: best_cost,
+            items: Some(best_solution.into_iter().enumerate().fold(
+                vec![false; problem.size],
+                |mut acc, (i, x)| {
+                    acc[mapping[i]] = x;
+                    acc
+                },
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use super::*;
+
+    fn solver(colony_size: usize) -> AntColonySolver {
+        AntColonySolver {
+            colony_size,
+            iterations: 20,
+            evaporation: 0.1,
+            alpha: 1.0,
+            beta: 2.0,
+            seed: 42,
+        }
+    }
+
+    #[test]
+    fn ants_fit_and_fill_knapsack() {
+        assert_feasible(&solver(10));
+        for problem in &random_problems(3, 10, 15) {
+            let solution = solver(10).construction(problem);
+            let weight = check_solution(problem, &solution);
+            // ants walk until no other item fits
+            assert!(problem
+                .items
+                .iter()
+                .zip(solution.items.unwrap())
+                .all(|(item, used)| used || weight + item.weight > problem.max_weight));
+        }
+    }
+
+    #[test]
+    fn empty_colony_gives_empty_solution() {
+        let problem = &random_problems(1, 1, 10)[0];
+        let solution = solver(0).construction(problem);
+        assert_eq!(solution, Solution::empty(problem.id, problem.size));
+    }
+}
@@ -0,0 +1,239 @@
+use super::{
+    features::{features, Feature, Features},
+    DisplayError, Item, Methods, Problem, Solution, Solver, SolverTrait,
+};
+
+use std::str::FromStr;
+use std::sync::{atomic::AtomicBool, Arc, Mutex};
+
+// Chooses method by features of instance. Rules are tried in order and first one,
+// whose condition holds, decides.
+#[derive(Debug, Clone)]
+pub struct AutoSolver {
+    pub rules: Vec<AutoRule>,
+    // solver of every method used by rules
+    pub solvers: Vec<(Methods, Solver)>,
+    // choice for last problem
+    pub chosen: Arc<Mutex<Option<Choice>>>,
+}
+
+// Default rules, thresholds come from bench of pruning, dynamic-cost and dynamic-weight
+// on generated sets (uni, corr and strong correlation, n 20 to 500, capacities from 300
+// to 10^6 and max costs from 100 to 10^6):
+// - pruning is the fastest on all uni (correlation about 0) and corr (about 0.98) sets,
+//   by two orders of magnitude at n 100
+// - on strong sets (correlation 1) pruning is the fastest up to n 40, from n 60 it loses,
+//   at n 100 it takes 1.9 s against 15 ms of dynamic-cost
+// - capacity alone doesn't decide between decompositions, with capacity 500 and cells
+//   ratio 1.3 dynamic-cost is 1.4x faster, it wins up to ratio 1.3 at every capacity
+// - with cost table 4 to 25 times bigger than weight one, dynamic-weight is faster
+//   by 1.2x (ratio 3.8), 3.6x (6-8) and 10-16x (25)
+// - cost tables over 2^24 cells are solved by sparse states of dynamic-cost, which are
+//   2-3x faster with ratio from 126 up to 40000
+pub const DEFAULT_RULES: &str = "correlation<0.99:pruning,n<50:pruning,cells-ratio<2:dynamic-cost,cost-cells>16777216:dynamic-cost,dynamic-weight";
+
+// method chosen for problem, problem is kept to recognize repeated calls with it
+#[derive(Debug, Clone)]
+pub struct Choice {
+    id: u32,
+    max_weight: u32,
+    items: Vec<Item>,
+    pub method: Methods,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Comparison {
+    Less,
+    Greater,
+}
+
+// feature<value:method, feature>value:method or method alone, which always holds
+#[derive(Debug, Clone, Copy)]
+pub struct AutoRule {
+    pub condition: Option<(Feature, Comparison, f64)>,
+    pub method: Methods,
+}
+
+impl FromStr for AutoRule {
+    type Err = DisplayError;
+    fn from_str(rule: &str) -> Result<AutoRule, DisplayError> {
+        let (condition, method) = match rule.rsplit_once(':') {
+            Some((condition, method)) => (Some(condition), method),
+            None => (None, rule),
+        };
+        let condition = match condition {
+            Some(condition) => {
+                let (feature, comparison, value) = if let Some((f, v)) = condition.split_once('<') {
+                    (f, Comparison::Less, v)
+                } else if let Some((f, v)) = condition.split_once('>') {
+                    (f, Comparison::Greater, v)
+                } else {
+                    return Err(format!(
+                        "Auto rule condition {:?} has to be feature<value or feature>value.",
+                        condition
+                    )
+                    .into());
+                };
+                let value = value.trim().parse::<f64>().map_err(|_| {
+                    DisplayError::from(format!("Auto rule value {:?} is not a number.", value))
+                })?;
+                Some((feature.trim().parse()?, comparison, value))
+            }
+            None => None,
+        };
+        Ok(AutoRule {
+            condition,
+            method: method.trim().parse()?,
+        })
+    }
+}
+
+// method of first rule, which holds for features
+pub fn rule_method(rules: &[AutoRule], features: &Features) -> Option<Methods> {
+    rules
+        .iter()
+        .find(|rule| match rule.condition {
+            Some((feature, Comparison::Less, value)) => features.value(feature) < value,
+            Some((feature, Comparison::Greater, value)) => features.value(feature) > value,
+            None => true,
+        })
+        .map(|rule| rule.method)
+}
+
+impl AutoSolver {
+    pub fn last_chosen(&self) -> Option<Methods> {
+        self.chosen
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|choice| choice.method)
+    }
+
+    // features are computed once per problem, memory estimate and construction share them
+    fn choose(&self, problem: &Problem) -> &Solver {
+        let mut chosen = self.chosen.lock().unwrap();
+        let method = match &*chosen {
+            Some(choice)
+                if choice.id == problem.id
+                    && choice.max_weight == problem.max_weight
+                    && choice.items == problem.items =>
+            {
+                choice.method
+            }
+            _ => {
+                let features = features(problem);
+                // last rule is without condition, checked by from_opts
+                let method = rule_method(&self.rules, &features).unwrap();
+                *chosen = Some(Choice {
+                    id: problem.id,
+                    max_weight: problem.max_weight,
+                    items: problem.items.clone(),
+                    method,
+                });
+                method
+            }
+        };
+        &self
+            .solvers
+            .iter()
+            .find(|(solver_method, _)| *solver_method == method)
+            .unwrap()
+            .1
+    }
+}
+
+impl SolverTrait for AutoSolver {
+    fn memory_estimate(&self, problem: &Problem) -> usize {
+        self.choose(problem).memory_estimate(problem)
+    }
+
+    fn construction(&self, problem: &Problem) -> Solution {
+        self.choose(problem).construction(problem)
+    }
+
+    fn decision(&self, problem: &Problem) -> Solution {
+        self.choose(problem).decision(problem)
+    }
+
+    fn cancellable_construction(&self, problem: &Problem, cancel: &AtomicBool) -> Option<Solution> {
+        self.choose(problem)
+            .cancellable_construction(problem, cancel)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_utils::*, Opts};
+    use super::*;
+    use structopt::StructOpt;
+
+    fn default_rules() -> Vec<AutoRule> {
+        DEFAULT_RULES
+            .split(',')
+            .map(|rule| rule.parse().unwrap())
+            .collect()
+    }
+
+    // correlated items, weights and costs are scaled index plus small noise
+    fn correlated(size: usize, weight_scale: u32, cost_scale: u32) -> Problem {
+        let items = (1..=size as u32)
+            .map(|i| (i * weight_scale + i % 5, i * cost_scale + i % 3))
+            .collect::<Vec<_>>();
+        let total_weight = items.iter().map(|x| x.0).sum::<u32>();
+        problem(0, total_weight / 2, &items)
+    }
+
+    #[test]
+    fn each_default_rule_picks_its_method() {
+        let rules = default_rules();
+        let method = |problem: &Problem| rule_method(&rules, &features(problem)).unwrap();
+        // uncorrelated
+        assert_eq!(method(&random_problems(18, 1, 60)[0]), Methods::Pruning);
+        // correlated, but small
+        assert_eq!(method(&correlated(20, 10, 10)), Methods::Pruning);
+        // correlated and big, tables of similar size or smaller cost one
+        assert_eq!(method(&correlated(60, 10, 10)), Methods::DynamicCost);
+        assert_eq!(method(&correlated(60, 1000, 1)), Methods::DynamicCost);
+        // cost table too big for dense table
+        assert_eq!(method(&correlated(60, 1, 1000)), Methods::DynamicCost);
+        // cost table dense, but much bigger than weight one
+        assert_eq!(method(&correlated(60, 10, 200)), Methods::DynamicWeight);
+    }
+
+    #[test]
+    fn parse_rules() {
+        let rule = "capacity-ratio>0.5:dynamic-cost"
+            .parse::<AutoRule>()
+            .unwrap();
+        assert!(matches!(
+            rule.condition,
+            Some((Feature::CapacityRatio, Comparison::Greater, v)) if v == 0.5
+        ));
+        assert_eq!(rule.method, Methods::DynamicCost);
+        assert!("naive".parse::<AutoRule>().unwrap().condition.is_none());
+        assert!("n=5:naive".parse::<AutoRule>().is_err());
+        assert!("n<x:naive".parse::<AutoRule>().is_err());
+        assert!("size<5:naive".parse::<AutoRule>().is_err());
+    }
+
+    #[test]
+    fn chosen_solver_is_exact_and_logged() {
+        let opts = Opts::from_iter_safe(&["knapsack"]).unwrap();
+        let solver = Solver::from_method(Methods::Auto, &opts).unwrap();
+        assert!(solver.is_exact());
+        assert_exact(&solver);
+        let auto = match solver {
+            Solver::Auto(auto) => auto,
+            _ => unreachable!(),
+        };
+        let problem = correlated(60, 1000, 1);
+        auto.memory_estimate(&problem);
+        assert_eq!(auto.last_chosen(), Some(Methods::DynamicCost));
+        // same id, but different items are chosen again
+        auto.construction(&random_problems(18, 1, 60)[0]);
+        assert_eq!(auto.last_chosen(), Some(Methods::Pruning));
+        let problem = correlated(60, 10, 200);
+        assert_eq!(auto.construction(&problem).cost, optimum(&problem));
+        assert_eq!(auto.last_chosen(), Some(Methods::DynamicWeight));
+    }
+}
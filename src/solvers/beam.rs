@@ -0,0 +1,207 @@
+use super::{
+    bounds::{Bounds, UpperBound},
+    sort_by_cost_weight_ratio, Item, Problem, Solution, SolverTrait,
+};
+
+use std::cmp::Reverse;
+
+// Both solvers walk the same decision tree as pruning (items by cost/weight ratio,
+// item is added or skipped), but they don't search it whole. 0 discrepancies gives greedy
+// solution, width 1 follows single path of best upper bounds, with growing width
+// or discrepancies they converge to exact solution.
+
+// breadth first search, which keeps only `width` best partial solutions by upper bound per level
+#[derive(Debug, Clone)]
+pub struct BeamSearchSolver {
+    pub width: usize,
+    pub bound: UpperBound,
+}
+
+// depth first search, which may go against greedy choice (skip fitting item)
+// at most `discrepancies` times on path
+#[derive(Debug, Clone)]
+pub struct LimitedDiscrepancySolver {
+    pub discrepancies: usize,
+    pub bound: UpperBound,
+}
+
+fn map_solution(problem: &Problem, state: &[bool], mapping: &[usize]) -> Vec<bool> {
+    state
+        .iter()
+        .enumerate()
+        .fold(vec![false; problem.size], |mut acc, (i, &x)| {
+            acc[mapping[i]] = x;
+            acc
+        })
+}
+
+impl SolverTrait for BeamSearchSolver {
+    fn construction(&self, problem: &Problem) -> Solution {
+        let (items, mapping) = sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
+
+        if items.is_empty() {
+            return Solution::empty(problem.id, problem.size);
+        }
+
+        let max_weight = problem.max_weight;
+        let bounds = Bounds::new(&items, max_weight, self.bound);
+
+        // every partial solution fits, so its cost is lower bound
+        let mut best_cost = 0;
+        let mut best_solution = vec![false; items.len()];
+
+        items.iter().enumerate().fold(
+            vec![(0, 0, Vec::with_capacity(items.len()))],
+            |beam: Vec<(u32, u32, Vec<bool>)>, (index, item)| {
+                let mut next = beam
+                    .into_iter()
+                    .flat_map(|(cost, weight, state)| {
+                        let with_item = if weight + item.weight <= max_weight {
+                            let mut state = state.clone();
+                            state.push(true);
+                            Some((cost + item.cost, weight + item.weight, state))
+                        } else {
+                            None
+                        };
+                        let mut state = state;
+                        state.push(false);
+                        with_item.into_iter().chain(Some((cost, weight, state)))
+                    })
+                    .map(|(cost, weight, state)| {
+                        (bounds.upper(index + 1, cost, weight), cost, weight, state)
+                    })
+                    .collect::<Vec<_>>();
+                if let Some(best) = next.iter().filter(|x| x.1 > best_cost).max_by_key(|x| x.1) {
+                    best_cost = best.1;
+                    best_solution[..best.3.len()].copy_from_slice(&best.3);
+                    best_solution[best.3.len()..]
+                        .iter_mut()
+                        .for_each(|x| *x = false);
+                }
+                next.retain(|x| x.0 >= best_cost);
+                // stable sort keeps states with item first on same bound
+                next.sort_by_key(|x| Reverse(x.0));
+                next.truncate(self.width.max(1));
+                next.into_iter()
+                    .map(|(_, cost, weight, state)| (cost, weight, state))
+                    .collect()
+            },
+        );
+
+        Solution {
+            id: problem.id,
+            size: problem.size,
+            cost: best_cost,
+            items: Some(map_solution(problem, &best_solution, &mapping)),
+        }
+    }
+}
+
+struct Discrepancy<'a> {
+    items: &'a [Item],
+    bounds: Bounds,
+    state: Vec<bool>,
+    best_cost: u32,
+    best_solution: Vec<bool>,
+}
+
+impl Discrepancy<'_> {
+    fn search(&mut self, index: usize, cost: u32, weight: u32, discrepancies: usize) {
+        if self.bounds.upper(index, cost, weight) <= self.best_cost {
+            return;
+        }
+        if index == self.items.len() {
+            self.best_cost = cost;
+            self.best_solution.copy_from_slice(&self.state);
+            return;
+        }
+        let item = self.items[index];
+        if weight + item.weight <= self.bounds.max_weight() {
+            self.state[index] = true;
+            self.search(
+                index + 1,
+                cost + item.cost,
+                weight + item.weight,
+                discrepancies,
+            );
+            self.state[index] = false;
+            if discrepancies > 0 {
+                self.search(index + 1, cost, weight, discrepancies - 1);
+            }
+        } else {
+            // skipping item which doesn't fit is not discrepancy
+            self.search(index + 1, cost, weight, discrepancies);
+        }
+    }
+}
+
+impl SolverTrait for LimitedDiscrepancySolver {
+    fn construction(&self, problem: &Problem) -> Solution {
+        let (items, mapping) = sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
+
+        if items.is_empty() {
+            return Solution::empty(problem.id, problem.size);
+        }
+
+        let mut search = Discrepancy {
+            items: &items,
+            bounds: Bounds::new(&items, problem.max_weight, self.bound),
+            state: vec![false; items.len()],
+            best_cost: 0,
+            best_solution: vec![false; items.len()],
+        };
+
+        // iterations with growing limit find good solutions early, which prunes later ones more
+        for discrepancies in 0..=self.discrepancies {
+            search.search(0, 0, 0, discrepancies);
+        }
+
+        Solution {
+            id: problem.id,
+            size: problem.size,
+            cost: search.best_cost,
+            items: Some(map_solution(problem, &search.best_solution, &mapping)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{greedy::GreedySolver, test_utils::*};
+    use super::*;
+
+    fn beam(width: usize) -> BeamSearchSolver {
+        BeamSearchSolver {
+            width,
+            bound: UpperBound::Dantzig,
+        }
+    }
+
+    fn discrepancy(discrepancies: usize) -> LimitedDiscrepancySolver {
+        LimitedDiscrepancySolver {
+            discrepancies,
+            bound: UpperBound::Dantzig,
+        }
+    }
+
+    #[test]
+    fn no_discrepancy_is_greedy() {
+        for problem in &random_problems(10, 20, 15) {
+            let greedy = GreedySolver().construction(problem).cost;
+            assert_eq!(discrepancy(0).construction(problem).cost, greedy);
+        }
+    }
+
+    #[test]
+    fn partial_search_fits() {
+        assert_feasible(&beam(1));
+        assert_feasible(&beam(4));
+        assert_feasible(&discrepancy(2));
+    }
+
+    #[test]
+    fn unlimited_search_is_exact() {
+        assert_exact(&beam(1 << 15));
+        assert_exact(&discrepancy(15));
+    }
+}
@@ -0,0 +1,336 @@
+use super::{
+    utils::{calc_remaining_cost, calc_remaining_weight, max_cost_from_rem},
+    DisplayError, Item,
+};
+
+use itertools::{
+    FoldWhile::{Continue, Done},
+    Itertools,
+};
+use std::str::FromStr;
+
+// items around break item, which are fixed in all combinations by enumerative bound
+const ENUMERATED_ITEMS: usize = 4;
+
+// steps of ternary search of lagrangian and surrogate multipliers
+const MULTIPLIER_STEPS: usize = 30;
+
+#[derive(Debug, Clone, Copy)]
+pub enum UpperBound {
+    // LP relaxation, fraction of break item fills capacity
+    Dantzig,
+    // break item is either excluded or included, Martello & Toth U2
+    MartelloToth,
+    // all combinations of items around break item, LP relaxation of the rest
+    Enumerative,
+    // cardinality constraint (at most as many items as the lightest ones which fit)
+    // is relaxed into costs by multiplier
+    Lagrangian,
+    // cardinality constraint is added to capacity constraint with multiplier
+    Surrogate,
+}
+
+impl FromStr for UpperBound {
+    type Err = DisplayError;
+    fn from_str(name: &str) -> Result<UpperBound, DisplayError> {
+        match name {
+            "dantzig" => Ok(UpperBound::Dantzig),
+            "martello-toth" => Ok(UpperBound::MartelloToth),
+            "enumerative" => Ok(UpperBound::Enumerative),
+            "lagrangian" => Ok(UpperBound::Lagrangian),
+            "surrogate" => Ok(UpperBound::Surrogate),
+            _ => Err(format!(
+                "Bound {:?} not found, following are valid: dantzig, martello-toth, enumerative, lagrangian, surrogate.",
+                name
+            )
+            .into()),
+        }
+    }
+}
+
+// upper bounds of subproblems, where items before index are decided, items are sorted by ratio
+#[derive(Debug, Clone)]
+pub struct Bounds {
+    items: Vec<Item>,
+    rem_cost: Vec<u32>,
+    rem_weight: Vec<u32>,
+    // indices of items by weight ascending
+    by_weight: Vec<usize>,
+    max_weight: u32,
+    kind: UpperBound,
+}
+
+// cost of fraction of item, which fills capacity, None for item without weight
+fn fraction(item: &Item, capacity: u32) -> Option<u32> {
+    (item.cost as u64 * capacity as u64)
+        .checked_div(item.weight as u64)
+        .map(|cost| cost as u32)
+}
+
+// LP relaxation of items sorted by ratio
+pub fn dantzig<'a>(mut items: impl Iterator<Item = &'a Item>, capacity: u32) -> u32 {
+    #[allow(deprecated)] // fold_while no longer deprecated in master
+    items
+        .fold_while((capacity, 0), |(capacity, cost), item| {
+            if item.weight <= capacity {
+                Continue((capacity - item.weight, cost + item.cost))
+            } else {
+                Done((0, cost + fraction(item, capacity).unwrap_or(0)))
+            }
+        })
+        .into_inner()
+        .1
+}
+
+// LP relaxation of unsorted (cost, weight) items, items with no cost are left out
+fn relaxation(mut items: Vec<(f64, f64)>, capacity: f64) -> f64 {
+    items.retain(|&(cost, _)| cost > 0.0);
+    items.sort_by(|l, r| (r.0 * l.1).partial_cmp(&(l.0 * r.1)).unwrap());
+    items
+        .into_iter()
+        .fold((capacity, 0.0), |(capacity, cost), item| {
+            if item.1 <= capacity {
+                (capacity - item.1, cost + item.0)
+            } else {
+                (0.0, cost + item.0 * capacity / item.1)
+            }
+        })
+        .1
+}
+
+// smallest value found by ternary search, every value has to be upper bound,
+// function is convex (lagrangian) or quasiconvex (surrogate) in multiplier
+fn minimize(value: impl Fn(f64) -> f64, mut low: f64, mut high: f64) -> f64 {
+    let mut best = value(low).min(value(high));
+    for _ in 0..MULTIPLIER_STEPS {
+        let (l, r) = (low + (high - low) / 3.0, high - (high - low) / 3.0);
+        let (l_value, r_value) = (value(l), value(r));
+        best = best.min(l_value).min(r_value);
+        if l_value <= r_value {
+            high = r;
+        } else {
+            low = l;
+        }
+    }
+    // rounding errors of floats must not cut off optimum
+    (best * (1.0 + 1e-12) + 1e-6).floor()
+}
+
+impl Bounds {
+    pub fn new(items: &[Item], max_weight: u32, kind: UpperBound) -> Bounds {
+        Bounds {
+            items: items.to_vec(),
+            rem_cost: calc_remaining_cost(items),
+            rem_weight: calc_remaining_weight(items),
+            by_weight: (0..items.len())
+                .sorted_by_key(|&i| items[i].weight)
+                .collect(),
+            max_weight,
+            kind,
+        }
+    }
+
+    pub fn max_weight(&self) -> u32 {
+        self.max_weight
+    }
+
+    // best cost reachable from partial solution of given cost and weight
+    pub fn upper(&self, index: usize, cost: u32, weight: u32) -> u32 {
+        if index >= self.items.len() {
+            return cost;
+        }
+        let capacity = self.max_weight - weight;
+        // O(log n)
+        let dantzig =
+            max_cost_from_rem(&self.rem_cost[index..], &self.rem_weight[index..], capacity);
+        cost + match self.kind {
+            UpperBound::Dantzig => dantzig,
+            UpperBound::MartelloToth => dantzig.min(self.martello_toth(index, capacity)),
+            UpperBound::Enumerative => dantzig.min(self.enumerative(index, capacity)),
+            UpperBound::Lagrangian => dantzig.min(self.lagrangian(index, capacity)),
+            UpperBound::Surrogate => dantzig.min(self.surrogate(index, capacity)),
+        }
+    }
+
+    // first item from index, which doesn't fit together with all before it, items.len() if all fit
+    fn break_item(&self, index: usize, capacity: u32) -> usize {
+        let total = self.rem_weight[index];
+        index + self.rem_weight[index..].partition_point(|&rem| total - rem <= capacity) - 1
+    }
+
+    // O(log n)
+    fn martello_toth(&self, index: usize, capacity: u32) -> u32 {
+        let b = self.break_item(index, capacity);
+        if b == self.items.len() {
+            return self.rem_cost[index];
+        }
+        let fitted_cost = self.rem_cost[index] - self.rem_cost[b];
+        let residual = capacity - (self.rem_weight[index] - self.rem_weight[b]);
+        let break_item = self.items[b];
+
+        // residual capacity is filled by next item ratio
+        let without_break = match self.items.get(b + 1) {
+            Some(next) => fraction(next, residual),
+            None => Some(0),
+        };
+        // overweight of break item is removed by previous item ratio
+        let with_break = match b.checked_sub(1).filter(|&prev| prev >= index) {
+            Some(prev) => {
+                let prev = self.items[prev];
+                let overweight = (break_item.weight - residual) as u64;
+                (overweight * prev.cost as u64 + prev.weight as u64 - 1)
+                    .checked_div(prev.weight as u64)
+                    .map(|removed| (break_item.cost as u64).saturating_sub(removed) as u32)
+            }
+            // break item alone is heavier than capacity
+            None => Some(0),
+        };
+        match (without_break, with_break) {
+            (Some(without_break), Some(with_break)) => fitted_cost + without_break.max(with_break),
+            // items without weight have no ratio, bound is left to dantzig
+            _ => u32::MAX,
+        }
+    }
+
+    // O(2^ENUMERATED_ITEMS * n)
+    fn enumerative(&self, index: usize, capacity: u32) -> u32 {
+        let b = self.break_item(index, capacity);
+        if b == self.items.len() {
+            return self.rem_cost[index];
+        }
+        let start = b.saturating_sub(ENUMERATED_ITEMS / 2).max(index);
+        let end = (start + ENUMERATED_ITEMS).min(self.items.len());
+        let enumerated = &self.items[start..end];
+        let rest = || self.items[index..start].iter().chain(&self.items[end..]);
+
+        (0..1usize << enumerated.len())
+            .filter_map(|mask| {
+                let (cost, weight) = enumerated
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| mask & (1 << i) != 0)
+                    .fold((0, 0), |(cost, weight), (_, item)| {
+                        (cost + item.cost, weight + item.weight)
+                    });
+                if weight <= capacity {
+                    Some(cost + dantzig(rest(), capacity - weight))
+                } else {
+                    None
+                }
+            })
+            .max()
+            .unwrap()
+    }
+
+    // most items from index, which fit together, the lightest ones
+    fn cardinality(&self, index: usize, capacity: u32) -> usize {
+        self.by_weight
+            .iter()
+            .filter(|&&i| i >= index)
+            .scan(0u64, |weight, &i| {
+                *weight += self.items[i].weight as u64;
+                Some(*weight)
+            })
+            .take_while(|&weight| weight <= capacity as u64)
+            .count()
+    }
+
+    // min over lambda >= 0 of lambda * k + LP relaxation with costs c - lambda,
+    // O(MULTIPLIER_STEPS * n log n)
+    fn lagrangian(&self, index: usize, capacity: u32) -> u32 {
+        let k = self.cardinality(index, capacity) as f64;
+        let items = &self.items[index..];
+        let value = |lambda: f64| {
+            lambda * k
+                + relaxation(
+                    items
+                        .iter()
+                        .map(|x| (x.cost as f64 - lambda, x.weight as f64))
+                        .collect(),
+                    capacity as f64,
+                )
+        };
+        let max_cost = items.iter().map(|x| x.cost).max().unwrap_or(0);
+        minimize(value, 0.0, max_cost as f64).min(u32::MAX as f64) as u32
+    }
+
+    // min over mu >= 0 of LP relaxation with weights w + mu and capacity c + mu * k,
+    // O(MULTIPLIER_STEPS * n log n)
+    fn surrogate(&self, index: usize, capacity: u32) -> u32 {
+        let k = self.cardinality(index, capacity) as f64;
+        let items = &self.items[index..];
+        let value = |mu: f64| {
+            relaxation(
+                items
+                    .iter()
+                    .map(|x| (x.cost as f64, x.weight as f64 + mu))
+                    .collect(),
+                capacity as f64 + mu * k,
+            )
+        };
+        minimize(value, 0.0, capacity as f64).min(u32::MAX as f64) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{sort_by_cost_weight_ratio, test_utils::*, Problem, PruningSolver};
+    use super::*;
+
+    const KINDS: [UpperBound; 5] = [
+        UpperBound::Dantzig,
+        UpperBound::MartelloToth,
+        UpperBound::Enumerative,
+        UpperBound::Lagrangian,
+        UpperBound::Surrogate,
+    ];
+
+    #[test]
+    fn bounds_are_not_below_optimum_of_subproblem() {
+        for problem in &random_problems(11, 10, 12) {
+            let (items, _) = sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
+            for &kind in &KINDS {
+                let bounds = Bounds::new(&items, problem.max_weight, kind);
+                let dantzig = Bounds::new(&items, problem.max_weight, UpperBound::Dantzig);
+                for index in 0..=items.len() {
+                    for &weight in &[0, problem.max_weight / 3, problem.max_weight] {
+                        let rest = Problem {
+                            max_weight: problem.max_weight - weight,
+                            size: items.len() - index,
+                            items: items[index..].to_vec(),
+                            ..problem.clone()
+                        };
+                        let upper = bounds.upper(index, 5, weight);
+                        assert!(upper >= 5 + optimum(&rest), "{:?} {}", kind, index);
+                        assert!(upper <= dantzig.upper(index, 5, weight));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pruning_is_exact_with_every_bound() {
+        for &bound in &KINDS {
+            assert_exact(&PruningSolver { bound });
+        }
+    }
+
+    #[test]
+    fn relaxed_cardinality_tightens_dantzig() {
+        // only one item fits, dantzig takes first and 5/6 of second
+        let items = problem(0, 10, &[(5, 10), (6, 11), (6, 11)]).items;
+        let (items, _) = sort_by_cost_weight_ratio(&items, 10);
+        let upper = |kind| Bounds::new(&items, 10, kind).upper(0, 0, 0);
+        assert_eq!(upper(UpperBound::Dantzig), 19);
+        assert_eq!(upper(UpperBound::Lagrangian), 11);
+        assert!(upper(UpperBound::Surrogate) < 19);
+    }
+
+    #[test]
+    fn parse_bounds() {
+        assert!(matches!("lagrangian".parse(), Ok(UpperBound::Lagrangian)));
+        assert!(matches!("surrogate".parse(), Ok(UpperBound::Surrogate)));
+        assert!("lp".parse::<UpperBound>().is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use super::{
+    dynamic_sparse::{dynamic_sparse, sparse_memory_estimate},
     greedy::construction_greedy_inner,
     ratio,
     utils::{
@@ -9,44 +10,78 @@ use super::{
 };
 use gcd::Gcd;
 use std::collections::VecDeque;
+use std::mem::size_of;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 #[derive(Debug, Clone)]
 pub struct DynamicCostSolver();
 
+// tables with more cells are solved by sparse states instead (one cell has 8 bytes)
+const SPARSE_TABLE_CELLS: usize = 1 << 24;
+
+// divides costs by their gcd, returns (cost gcd, capacity rounded down to multiple of weight gcd)
+fn normalize(items: &mut [Item], max_weight: u32) -> (u32, u32) {
+    let cost_gcd = items.iter().fold(items[0].cost, |acc, x| acc.gcd(x.cost));
+    let weight_gcd = items
+        .iter()
+        .fold(items[0].weight, |acc, x| acc.gcd(x.weight));
+
+    if cost_gcd > 1 {
+        for item in items.iter_mut() {
+            item.cost /= cost_gcd;
+        }
+    }
+
+    (cost_gcd, (max_weight - max_weight % weight_gcd).max(1))
+}
+
 impl SolverTrait for DynamicCostSolver {
+    fn memory_estimate(&self, problem: &Problem) -> usize {
+        let (mut items, _) = sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
+        if items.is_empty() {
+            return 0;
+        }
+        let (_, max_weight) = normalize(&mut items, problem.max_weight);
+        let cells = (max_cost(&items, max_weight) as usize + 1).saturating_mul(items.len() + 1);
+        if cells > SPARSE_TABLE_CELLS {
+            sparse_memory_estimate(problem)
+        } else {
+            cells * size_of::<Option<u32>>()
+        }
+    }
+
     fn construction(&self, problem: &Problem) -> Solution {
+        self.cancellable_construction(problem, &AtomicBool::new(false))
+            .unwrap()
+    }
+
+    fn cancellable_construction(&self, problem: &Problem, cancel: &AtomicBool) -> Option<Solution> {
         // mainly foward tracking but backtracing solution
         let (mut items, mut mappings) =
             sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
 
         if items.is_empty() {
             items.push(Item {
-                weight: std::u32::MAX,
+                weight: u32::MAX,
                 cost: 0,
             });
             mappings.push(0);
         }
 
-        let cost_gcd = items.iter().fold(items[0].cost, |acc, x| acc.gcd(x.cost));
-        let weight_gcd = items
-            .iter()
-            .fold(items[0].weight, |acc, x| acc.gcd(x.weight));
-        let max_weight = (problem.max_weight - problem.max_weight % weight_gcd).max(1);
+        let (cost_gcd, max_weight) = normalize(&mut items, problem.max_weight);
         let ilen = items.len();
 
-        if cost_gcd > 1 {
-            for item in &mut items {
-                item.cost /= cost_gcd;
-            }
-        }
-
         // if we take first k items and part of the first item, which do not fit, we get maximal possible cost
         let max_cost = max_cost(&items, max_weight);
 
         let size = max_cost as usize + 1;
 
         if max_cost == 0 {
-            return Solution::empty(problem.id, problem.size);
+            return Some(Solution::empty(problem.id, problem.size));
+        }
+
+        if size.saturating_mul(ilen + 1) > SPARSE_TABLE_CELLS {
+            return dynamic_sparse(problem, cancel);
         }
 
         let rem_cost = calc_remaining_cost(&items);
@@ -87,6 +122,9 @@ impl SolverTrait for DynamicCostSolver {
         let mut best_cost = redux_solution.1 / cost_gcd;
 
         while !queue.is_empty() {
+            if cancel.load(Ordering::Relaxed) {
+                return None;
+            }
             let (item, cost) = queue.pop_front().unwrap();
             if item >= ilen {
                 continue;
@@ -166,11 +204,41 @@ impl SolverTrait for DynamicCostSolver {
                 .3
         };
 
-        Solution {
+        Some(Solution {
             id: problem.id,
             size: problem.size,
             cost: best_cost * cost_gcd,
             items: Some(best_solution),
-        }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{dynamic_sparse::sparse_memory_estimate, test_utils::*};
+    use super::*;
+
+    #[test]
+    fn agrees_with_dynamic_weight() {
+        assert_exact(&DynamicCostSolver());
+    }
+
+    #[test]
+    fn memory_estimate_of_table() {
+        // costs are divided by gcd 30 to 2, 1, 1, 1, bound is 2 + 1 + 1/2 rounded down
+        let problem = problem(0, 6, &[(2, 30), (2, 30), (2, 30), (3, 60)]);
+        assert_eq!(
+            DynamicCostSolver().memory_estimate(&problem),
+            (3 + 1) * (4 + 1) * size_of::<Option<u32>>()
+        );
+    }
+
+    #[test]
+    fn memory_estimate_of_sparse_states() {
+        let problem = problem(0, 10, &[(1, 10_000_000), (2, 20_000_001), (3, 1)]);
+        assert_eq!(
+            DynamicCostSolver().memory_estimate(&problem),
+            sparse_memory_estimate(&problem)
+        );
     }
 }
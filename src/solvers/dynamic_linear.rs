@@ -0,0 +1,208 @@
+use super::{
+    utils::{max_cost, sort_by_cost_weight_ratio},
+    Item, Problem, Solution, SolverTrait,
+};
+use gcd::Gcd;
+use std::mem::size_of;
+
+// Both solvers keep only one row of table at time and reconstruct solution
+// by divide and conquer (Hirschberg): optimal solution of items split into halves
+// is made of optimal solutions of both halves, capacity (or cost) split between them
+// is found from profiles of halves. Memory is O(capacity) per pass, time O(n * capacity * log n).
+
+#[derive(Debug, Clone)]
+pub struct DynamicWeightLinearSolver();
+
+#[derive(Debug, Clone)]
+pub struct DynamicCostLinearSolver();
+
+// profile[w] is maximal cost of items with weight at most w
+fn weight_profile(items: &[Item], max_weight: u32) -> Vec<u32> {
+    items
+        .iter()
+        .fold(vec![0; max_weight as usize + 1], |mut row, item| {
+            for w in (item.weight as usize..row.len()).rev() {
+                row[w] = row[w].max(row[w - item.weight as usize] + item.cost);
+            }
+            row
+        })
+}
+
+// profile[c] is minimal weight of items with cost exactly c, u32::MAX if unreachable
+fn cost_profile(items: &[Item], max_cost: u32) -> Vec<u32> {
+    let mut first_row = vec![u32::MAX; max_cost as usize + 1];
+    first_row[0] = 0;
+    items.iter().fold(first_row, |mut row, item| {
+        for c in (item.cost as usize..row.len()).rev() {
+            row[c] = row[c].min(row[c - item.cost as usize].saturating_add(item.weight));
+        }
+        row
+    })
+}
+
+// items are optimally packed into max_weight, sets used ones into solution
+fn weight_reconstruct(items: &[Item], max_weight: u32, solution: &mut [bool]) {
+    if let [item] = items {
+        solution[0] = item.weight <= max_weight && item.cost > 0;
+        return;
+    }
+    let mid = items.len() / 2;
+    let left_weight = {
+        let left = weight_profile(&items[..mid], max_weight);
+        let right = weight_profile(&items[mid..], max_weight);
+        (0..=max_weight)
+            .max_by_key(|&w| left[w as usize] + right[(max_weight - w) as usize])
+            .unwrap()
+    };
+    // profiles are dropped before descent, so they don't pile up with recursion depth
+    let (left_solution, right_solution) = solution.split_at_mut(mid);
+    weight_reconstruct(&items[..mid], left_weight, left_solution);
+    weight_reconstruct(&items[mid..], max_weight - left_weight, right_solution);
+}
+
+// items have to reach exactly cost with minimal weight, sets used ones into solution
+fn cost_reconstruct(items: &[Item], cost: u32, solution: &mut [bool]) {
+    if let [item] = items {
+        solution[0] = cost > 0 && item.cost == cost;
+        return;
+    }
+    let mid = items.len() / 2;
+    let left_cost = {
+        let left = cost_profile(&items[..mid], cost);
+        let right = cost_profile(&items[mid..], cost);
+        (0..=cost)
+            .min_by_key(|&c| left[c as usize].saturating_add(right[(cost - c) as usize]))
+            .unwrap()
+    };
+    let (left_solution, right_solution) = solution.split_at_mut(mid);
+    cost_reconstruct(&items[..mid], left_cost, left_solution);
+    cost_reconstruct(&items[mid..], cost - left_cost, right_solution);
+}
+
+fn map_solution(problem: &Problem, solution: &[bool], mappings: &[usize]) -> Vec<bool> {
+    solution.iter().zip(mappings.iter()).fold(
+        vec![false; problem.size],
+        |mut acc, (&used, &mapping)| {
+            acc[mapping] = used;
+            acc
+        },
+    )
+}
+
+impl SolverTrait for DynamicWeightLinearSolver {
+    // two profiles are alive at once
+    fn memory_estimate(&self, problem: &Problem) -> usize {
+        (problem.max_weight as usize + 1) * 2 * size_of::<u32>()
+    }
+
+    fn construction(&self, problem: &Problem) -> Solution {
+        let (mut items, mappings) = sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
+
+        if items.is_empty() {
+            return Solution::empty(problem.id, problem.size);
+        }
+
+        let gcd = items
+            .iter()
+            .fold(items[0].weight, |acc, x| acc.gcd(x.weight));
+
+        if gcd > 1 {
+            for item in &mut items {
+                item.weight /= gcd;
+            }
+        }
+
+        let max_weight = problem.max_weight / gcd;
+
+        let mut solution = vec![false; items.len()];
+        weight_reconstruct(&items, max_weight, &mut solution);
+
+        Solution {
+            id: problem.id,
+            size: problem.size,
+            cost: items
+                .iter()
+                .zip(solution.iter())
+                .filter(|(_, &used)| used)
+                .map(|(item, _)| item.cost)
+                .sum(),
+            items: Some(map_solution(problem, &solution, &mappings)),
+        }
+    }
+}
+
+impl SolverTrait for DynamicCostLinearSolver {
+    fn memory_estimate(&self, problem: &Problem) -> usize {
+        let (items, _) = sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
+        (max_cost(&items, problem.max_weight) as usize + 1) * 2 * size_of::<u32>()
+    }
+
+    fn construction(&self, problem: &Problem) -> Solution {
+        let (mut items, mappings) = sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
+
+        if items.is_empty() {
+            return Solution::empty(problem.id, problem.size);
+        }
+
+        let cost_gcd = items.iter().fold(items[0].cost, |acc, x| acc.gcd(x.cost));
+
+        if cost_gcd > 1 {
+            for item in &mut items {
+                item.cost /= cost_gcd;
+            }
+        }
+
+        let max_cost = max_cost(&items, problem.max_weight);
+
+        // one pass over all items finds the best reachable cost
+        let best_cost = cost_profile(&items, max_cost)
+            .iter()
+            .rposition(|&weight| weight <= problem.max_weight)
+            .unwrap() as u32;
+
+        let mut solution = vec![false; items.len()];
+        cost_reconstruct(&items, best_cost, &mut solution);
+
+        Solution {
+            id: problem.id,
+            size: problem.size,
+            cost: best_cost * cost_gcd,
+            items: Some(map_solution(problem, &solution, &mappings)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_utils::*, DynamicWeightSolver};
+    use super::*;
+
+    #[test]
+    fn weight_linear_agrees_with_dynamic_weight() {
+        assert_exact(&DynamicWeightLinearSolver());
+    }
+
+    #[test]
+    fn cost_linear_agrees_with_dynamic_weight() {
+        assert_exact(&DynamicCostLinearSolver());
+    }
+
+    #[test]
+    fn profiles_of_small_items() {
+        let items = problem(0, 0, &[(2, 3), (3, 4), (4, 5)]).items;
+        assert_eq!(weight_profile(&items, 7), vec![0, 0, 3, 4, 5, 7, 8, 9]);
+        assert_eq!(
+            cost_profile(&items, 9),
+            vec![0, u32::MAX, u32::MAX, 2, 3, 4, u32::MAX, 5, 6, 7]
+        );
+    }
+
+    #[test]
+    fn uses_less_memory_than_table() {
+        let problem = &random_problems(1, 1, 40)[0];
+        assert!(
+            DynamicWeightLinearSolver().memory_estimate(problem)
+                < DynamicWeightSolver().memory_estimate(problem)
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use super::{
+    dynamic_simd::{bitset_memory_estimate, bitset_rows, row_update, LANES},
+    Problem, Solution, SolverTrait,
+};
+use rayon::{prelude::*, ThreadPool};
+use std::sync::Arc;
+
+// Weight decomposition computed row by row (one row per item), where row[w] is best cost
+// of already processed items with capacity w. Every cell of new row depends only on
+// previous row, so row is split into chunks of capacity, which are computed in parallel.
+// Only two rows are kept, decisions (item taken at capacity) are kept as bits.
+#[derive(Debug, Clone)]
+pub struct DynamicWeightParallelSolver {
+    pub pool: Arc<ThreadPool>,
+}
+
+// capacities computed by one task, multiple of LANES so chunk covers whole words of bitset
+const CHUNK: usize = 1 << 14;
+
+impl SolverTrait for DynamicWeightParallelSolver {
+    fn memory_estimate(&self, problem: &Problem) -> usize {
+        bitset_memory_estimate(problem)
+    }
+
+    fn construction(&self, problem: &Problem) -> Solution {
+        bitset_rows(problem, |prev, next, bits, item| {
+            self.pool.install(|| {
+                next.par_chunks_mut(CHUNK)
+                    .zip(bits.par_chunks_mut(CHUNK / LANES))
+                    .enumerate()
+                    .for_each(|(chunk, (cells, bits))| {
+                        row_update(
+                            prev,
+                            cells,
+                            bits,
+                            chunk * CHUNK,
+                            item.weight as usize,
+                            item.cost,
+                        )
+                    });
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use super::*;
+
+    fn solver(threads: usize) -> DynamicWeightParallelSolver {
+        DynamicWeightParallelSolver {
+            pool: Arc::new(
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .unwrap(),
+            ),
+        }
+    }
+
+    #[test]
+    fn agrees_with_dynamic_weight() {
+        assert_exact(&solver(2));
+    }
+
+    #[test]
+    fn rows_split_into_chunks() {
+        for problem in &heavy_problems(14, 3, 12) {
+            assert!(problem.max_weight as usize > 2 * CHUNK);
+            let solution = solver(3).construction(problem);
+            check_solution(problem, &solution);
+            assert_eq!(solution.cost, optimum(problem));
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use super::{utils::sort_by_cost_weight_ratio, Item, Problem, Solution, SolverTrait};
+use gcd::Gcd;
+use std::convert::TryInto;
+use std::mem::{size_of, swap};
+
+// Weight decomposition computed row by row with plain u32 cells (no Option), decisions
+// are kept in packed bitset. Row update is max of previous row and its shifted copy
+// plus item cost, kernel computes it in blocks of 64 cells without branches,
+// so compiler can vectorise it, and decisions of one block form one bitset word.
+#[derive(Debug, Clone)]
+pub struct DynamicWeightSimdSolver();
+
+pub const LANES: usize = 64;
+
+pub fn words(size: usize) -> usize {
+    size.div_ceil(LANES)
+}
+
+// computes cells of capacities start.. from previous row, start is multiple of LANES
+pub fn row_update(
+    prev: &[u32],
+    cells: &mut [u32],
+    bits: &mut [u64],
+    start: usize,
+    weight: usize,
+    cost: u32,
+) {
+    for ((block, word), block_start) in cells
+        .chunks_mut(LANES)
+        .zip(bits.iter_mut())
+        .zip((start..).step_by(LANES))
+    {
+        if block_start >= weight && block.len() == LANES {
+            let block: &mut [u32; LANES] = block.try_into().unwrap();
+            let without: &[u32; LANES] = prev[block_start..block_start + LANES].try_into().unwrap();
+            let with: &[u32; LANES] = prev[block_start - weight..block_start - weight + LANES]
+                .try_into()
+                .unwrap();
+            let mut mask = 0u64;
+            for k in 0..LANES {
+                let candidate = with[k] + cost;
+                let take = candidate > without[k];
+                block[k] = if take { candidate } else { without[k] };
+                mask |= (take as u64) << k;
+            }
+            *word = mask;
+        } else {
+            // block with capacities under item weight or last block of row
+            *word = 0;
+            for (k, cell) in block.iter_mut().enumerate() {
+                let w = block_start + k;
+                *cell = prev[w];
+                if w >= weight && prev[w - weight] + cost > *cell {
+                    *cell = prev[w - weight] + cost;
+                    *word |= 1 << k;
+                }
+            }
+        }
+    }
+}
+
+pub fn bitset_memory_estimate(problem: &Problem) -> usize {
+    let (items, _) = sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
+    let size = problem.max_weight as usize + 1;
+    (words(size) * size_of::<u64>())
+        .saturating_mul(items.len())
+        .saturating_add(2 * size * size_of::<u32>())
+}
+
+// runs compute_row(previous row, new row, decision bits of new row, item) for every item
+// and backtracks solution from decision bits
+pub fn bitset_rows<F>(problem: &Problem, compute_row: F) -> Solution
+where
+    F: Fn(&[u32], &mut [u32], &mut [u64], &Item),
+{
+    let (mut items, mapping) = sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
+
+    if items.is_empty() {
+        return Solution::empty(problem.id, problem.size);
+    }
+
+    let gcd = items
+        .iter()
+        .fold(items[0].weight, |acc, x| acc.gcd(x.weight));
+
+    if gcd > 1 {
+        for item in &mut items {
+            item.weight /= gcd;
+        }
+    }
+
+    let max_weight = (problem.max_weight / gcd) as usize;
+    let size = max_weight + 1;
+    let words = words(size);
+
+    let mut prev = vec![0u32; size];
+    let mut next = vec![0u32; size];
+    // bit w of row i says, that item i is taken with capacity w
+    let mut decisions = vec![0u64; words * items.len()];
+
+    for (item, bits) in items.iter().zip(decisions.chunks_mut(words)) {
+        compute_row(&prev, &mut next, bits, item);
+        swap(&mut prev, &mut next);
+    }
+
+    let best_solution = items
+        .iter()
+        .zip(decisions.chunks(words))
+        .zip(mapping.iter())
+        .rev()
+        .fold(
+            (max_weight, vec![false; problem.size]),
+            |(w, mut vec), ((item, bits), &mapping)| {
+                if bits[w / LANES] & (1 << (w % LANES)) != 0 {
+                    vec[mapping] = true;
+                    (w - item.weight as usize, vec)
+                } else {
+                    (w, vec)
+                }
+            },
+        )
+        .1;
+
+    Solution {
+        id: problem.id,
+        size: problem.size,
+        cost: prev[max_weight],
+        items: Some(best_solution),
+    }
+}
+
+impl SolverTrait for DynamicWeightSimdSolver {
+    fn memory_estimate(&self, problem: &Problem) -> usize {
+        bitset_memory_estimate(problem)
+    }
+
+    fn construction(&self, problem: &Problem) -> Solution {
+        bitset_rows(problem, |prev, next, bits, item| {
+            row_update(prev, next, bits, 0, item.weight as usize, item.cost)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use super::*;
+
+    #[test]
+    fn agrees_with_dynamic_weight() {
+        assert_exact(&DynamicWeightSimdSolver());
+        for problem in &heavy_problems(15, 3, 12) {
+            let solution = DynamicWeightSimdSolver().construction(problem);
+            check_solution(problem, &solution);
+            assert_eq!(solution.cost, optimum(problem));
+        }
+    }
+
+    #[test]
+    fn block_kernel_agrees_with_cell_loop() {
+        let prev = (0..300)
+            .map(|w| (w * 7 % 31 + w / 3) as u32)
+            .collect::<Vec<_>>();
+        let (weight, cost) = (70, 20);
+        let mut cells = vec![0; prev.len()];
+        let mut bits = vec![0; words(prev.len())];
+        row_update(&prev, &mut cells, &mut bits, 0, weight, cost);
+        for w in 0..prev.len() {
+            let take = w >= weight && prev[w - weight] + cost > prev[w];
+            let expected = if take {
+                prev[w - weight] + cost
+            } else {
+                prev[w]
+            };
+            assert_eq!(cells[w], expected, "capacity {}", w);
+            assert_eq!(bits[w / LANES] & (1 << (w % LANES)) != 0, take);
+        }
+    }
+}
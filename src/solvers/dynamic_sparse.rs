@@ -0,0 +1,211 @@
+use super::{
+    greedy::construction_greedy_inner,
+    utils::{
+        best_valued_item_fit, calc_remaining_cost, calc_remaining_weight, max_cost,
+        max_cost_from_rem, sort_by_cost_weight_ratio,
+    },
+    Problem, Solution, SolverTrait,
+};
+use itertools::Itertools;
+use std::cmp::Reverse;
+use std::mem::size_of;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[derive(Debug, Clone)]
+pub struct DynamicSparseSolver();
+
+// reachable states (cost, weight) after deciding first k items
+// sorted by weight (and so by cost too), dominated states are removed
+type Layer = Vec<(u32, u32)>;
+
+impl SolverTrait for DynamicSparseSolver {
+    fn construction(&self, problem: &Problem) -> Solution {
+        dynamic_sparse(problem, &AtomicBool::new(false)).unwrap()
+    }
+
+    fn cancellable_construction(&self, problem: &Problem, cancel: &AtomicBool) -> Option<Solution> {
+        dynamic_sparse(problem, cancel)
+    }
+
+    fn memory_estimate(&self, problem: &Problem) -> usize {
+        sparse_memory_estimate(problem)
+    }
+}
+
+// worst case, layer k has at most 2^k states and all of them have distinct costs and weights
+pub fn sparse_memory_estimate(problem: &Problem) -> usize {
+    let (items, _) = sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
+    let distinct =
+        (max_cost(&items, problem.max_weight) as usize).min(problem.max_weight as usize) + 1;
+    (0..=items.len())
+        .map(|k| match k {
+            k if k < usize::BITS as usize => distinct.min(1 << k),
+            _ => distinct,
+        })
+        .fold(0usize, |acc, states| acc.saturating_add(states))
+        .saturating_mul(size_of::<(u32, u32)>())
+}
+
+// states sorted by weight (better cost first on same weight), keeps only states,
+// which have bigger cost than all lighter states
+fn pareto_front(states: impl Iterator<Item = (u32, u32)>) -> Layer {
+    states.fold(Vec::new(), |mut layer: Layer, state| {
+        if layer.last().is_none_or(|last| state.0 > last.0) {
+            layer.push(state);
+        }
+        layer
+    })
+}
+
+// cost decomposition, which stores only reachable states instead of whole table
+// memory scales with pareto front of (cost, weight) states, not with maximal cost
+// returns None, when cancel is set before layers are computed
+pub fn dynamic_sparse(problem: &Problem, cancel: &AtomicBool) -> Option<Solution> {
+    let (items, mappings) = sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
+
+    if items.is_empty() {
+        return Some(Solution::empty(problem.id, problem.size));
+    }
+
+    let max_weight = problem.max_weight;
+    let rem_cost = calc_remaining_cost(&items);
+    let rem_weight = calc_remaining_weight(&items);
+
+    // redux solution is lower bound, states which can't reach it are thrown away
+    let lower_bound = construction_greedy_inner(&items, &mappings, problem.size, max_weight)
+        .1
+        .max(best_valued_item_fit(&items, max_weight).0);
+
+    let upper_bound = |index: usize, cost: u32, weight: u32| {
+        if index < items.len() {
+            cost + max_cost_from_rem(
+                &rem_cost[index..],
+                &rem_weight[index..],
+                max_weight - weight,
+            )
+        } else {
+            cost
+        }
+    };
+
+    let layers = items.iter().enumerate().try_fold(
+        vec![vec![(0, 0)]],
+        |mut layers: Vec<Layer>, (index, item)| {
+            if cancel.load(Ordering::Relaxed) {
+                return None;
+            }
+            let prev = layers.last().unwrap();
+            let with_item = prev
+                .iter()
+                .filter(|(_, weight)| weight + item.weight <= max_weight)
+                .map(|(cost, weight)| (cost + item.cost, weight + item.weight));
+            // merge by weight (better cost first on same weight)
+            let layer = prev
+                .iter()
+                .cloned()
+                .merge_by(with_item, |l, r| (l.1, Reverse(l.0)) <= (r.1, Reverse(r.0)))
+                .filter(|&(cost, weight)| upper_bound(index + 1, cost, weight) >= lower_bound);
+            layers.push(pareto_front(layer));
+            Some(layers)
+        },
+    )?;
+
+    // path to optimal solution is never pruned, so last layer is not empty
+    let (best_cost, best_weight) = *layers.last().unwrap().last().unwrap();
+
+    // state is reachable without item if it's already in previous layer
+    let best_solution = layers
+        .iter()
+        .rev()
+        .skip(1)
+        .zip(items.iter().zip(mappings.iter()).rev())
+        .fold(
+            (best_cost, best_weight, vec![false; problem.size]),
+            |(cost, weight, mut vec), (layer, (item, &mapping))| {
+                let without_item = layer
+                    .binary_search_by_key(&weight, |state| state.1)
+                    .is_ok_and(|i| layer[i].0 == cost);
+                if without_item {
+                    (cost, weight, vec)
+                } else {
+                    vec[mapping] = true;
+                    (cost - item.cost, weight - item.weight, vec)
+                }
+            },
+        )
+        .2;
+
+    Some(Solution {
+        id: problem.id,
+        size: problem.size,
+        cost: best_cost,
+        items: Some(best_solution),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{dynamic_cost::DynamicCostSolver, test_utils::*};
+    use super::*;
+
+    #[test]
+    fn agrees_with_dynamic_weight() {
+        assert_exact(&DynamicSparseSolver());
+    }
+
+    #[test]
+    fn dominated_states_are_removed() {
+        let states = vec![
+            (0, 0),
+            (5, 2),
+            (3, 3),
+            (5, 3),
+            (4, 4),
+            (8, 4),
+            (7, 4),
+            (9, 6),
+        ];
+        assert_eq!(
+            pareto_front(states.into_iter()),
+            vec![(0, 0), (5, 2), (8, 4), (9, 6)]
+        );
+    }
+
+    #[test]
+    fn backtracks_through_equal_states() {
+        // many subsets reach same (cost, weight), backtracking has to pick consistent one
+        let problem = problem(
+            0,
+            10,
+            &[(2, 3), (3, 4), (5, 7), (2, 3), (3, 4), (5, 7), (1, 1)],
+        );
+        let solution = dynamic_sparse(&problem, &AtomicBool::new(false)).unwrap();
+        assert_eq!(check_solution(&problem, &solution), 10);
+        assert_eq!(solution.cost, optimum(&problem));
+    }
+
+    #[test]
+    fn huge_costs_fall_back_from_cost_table() {
+        // cost table would have more than SPARSE_TABLE_CELLS cells
+        let problem = problem(
+            0,
+            60,
+            &(1..=20)
+                .map(|i| (i * 7 % 13 + 1, 10_000_000 + i * 104_729 % 99_991))
+                .collect::<Vec<_>>(),
+        );
+        let solution = DynamicCostSolver().construction(&problem);
+        check_solution(&problem, &solution);
+        assert_eq!(solution.cost, optimum(&problem));
+    }
+
+    #[test]
+    fn stops_when_cancelled() {
+        let problem = &random_problems(17, 1, 15)[0];
+        assert_eq!(dynamic_sparse(problem, &AtomicBool::new(true)), None);
+        assert_eq!(
+            DynamicSparseSolver().cancellable_construction(problem, &AtomicBool::new(false)),
+            Some(DynamicSparseSolver().construction(problem))
+        );
+    }
+}
@@ -1,18 +1,35 @@
-use super::{utils::sort_by_cost_weight_ratio, Item, Problem, Solution, SolverTrait};
+use super::{utils::sort_by_cost_weight_ratio, Problem, Solution, SolverTrait};
 use gcd::Gcd;
+use std::mem::size_of;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 #[derive(Debug, Clone)]
 pub struct DynamicWeightSolver();
 
 impl SolverTrait for DynamicWeightSolver {
+    fn memory_estimate(&self, problem: &Problem) -> usize {
+        let (items, _) = sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
+        let gcd = items
+            .iter()
+            .fold(items.first().map_or(1, |x| x.weight), |acc, x| {
+                acc.gcd(x.weight)
+            });
+        (problem.max_weight as usize / gcd.max(1) as usize + 1)
+            .saturating_mul(items.len().max(1) + 1)
+            .saturating_mul(size_of::<Option<(u32, bool)>>())
+    }
+
     fn construction(&self, problem: &Problem) -> Solution {
+        self.cancellable_construction(problem, &AtomicBool::new(false))
+            .unwrap()
+    }
+
+    fn cancellable_construction(&self, problem: &Problem, cancel: &AtomicBool) -> Option<Solution> {
         // backtracking only
-        let (mut items, mut mapping) =
-            sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
+        let (mut items, mapping) = sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
 
         if items.is_empty() {
-            items.push(Item { weight: 1, cost: 0 });
-            mapping.push(0);
+            return Some(Solution::empty(problem.id, problem.size));
         }
 
         let gcd = items
@@ -53,6 +70,9 @@ impl SolverTrait for DynamicWeightSolver {
 
         stack.push((0usize, 0u32));
         while !stack.is_empty() {
+            if cancel.load(Ordering::Relaxed) {
+                return None;
+            }
             let (item, weight) = stack.last().unwrap();
             let with_item = (item + 1, weight + items[*item].weight);
             let without_item = (item + 1, *weight);
@@ -77,7 +97,7 @@ impl SolverTrait for DynamicWeightSolver {
             table[*item][*weight as usize] = me_cell;
             stack.pop();
         }
-        Solution {
+        Some(Solution {
             id: problem.id,
             size: problem.size,
             cost: table[0][0].unwrap().0,
@@ -95,6 +115,6 @@ impl SolverTrait for DynamicWeightSolver {
                     )
                     .2,
             ),
-        }
+        })
     }
 }
@@ -0,0 +1,253 @@
+use super::{
+    bounds::dantzig,
+    greedy::construction_greedy_inner,
+    utils::{max_cost, sort_by_cost_weight_ratio},
+    DisplayError, Problem,
+};
+use gcd::Gcd;
+use std::fmt;
+use std::str::FromStr;
+
+// properties of instance, which decide speed of exact methods
+#[derive(Debug, Clone)]
+pub struct Features {
+    pub n: usize,
+    pub capacity: u32,
+    pub max_cost: u32,
+    pub total_weight: u64,
+    // capacity / total weight
+    pub capacity_ratio: f64,
+    // pearson correlation of weights and costs
+    pub correlation: f64,
+    pub weight_gcd: u32,
+    pub cost_gcd: u32,
+    // position of mean weight between lightest (-1) and heaviest (1) item
+    pub balance: f64,
+    // (LP relaxation - greedy cost) / LP relaxation
+    pub lp_gap: f64,
+    // cells of weight and cost decomposition tables
+    pub weight_cells: u64,
+    pub cost_cells: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    N,
+    Capacity,
+    MaxCost,
+    TotalWeight,
+    CapacityRatio,
+    Correlation,
+    WeightGcd,
+    CostGcd,
+    Balance,
+    LpGap,
+    WeightCells,
+    CostCells,
+    // cost cells / weight cells
+    CellsRatio,
+}
+
+const FEATURES: [(&str, Feature); 13] = [
+    ("n", Feature::N),
+    ("capacity", Feature::Capacity),
+    ("max-cost", Feature::MaxCost),
+    ("total-weight", Feature::TotalWeight),
+    ("capacity-ratio", Feature::CapacityRatio),
+    ("correlation", Feature::Correlation),
+    ("weight-gcd", Feature::WeightGcd),
+    ("cost-gcd", Feature::CostGcd),
+    ("balance", Feature::Balance),
+    ("lp-gap", Feature::LpGap),
+    ("weight-cells", Feature::WeightCells),
+    ("cost-cells", Feature::CostCells),
+    ("cells-ratio", Feature::CellsRatio),
+];
+
+impl FromStr for Feature {
+    type Err = DisplayError;
+    fn from_str(name: &str) -> Result<Feature, DisplayError> {
+        FEATURES
+            .iter()
+            .find(|(feature_name, _)| *feature_name == name)
+            .map(|(_, feature)| *feature)
+            .ok_or_else(|| {
+                format!(
+                    "Feature {:?} not found, following are valid: {}.",
+                    name,
+                    FEATURES.iter().map(|x| x.0).collect::<Vec<_>>().join(", ")
+                )
+                .into()
+            })
+    }
+}
+
+impl Features {
+    pub fn value(&self, feature: Feature) -> f64 {
+        match feature {
+            Feature::N => self.n as f64,
+            Feature::Capacity => self.capacity as f64,
+            Feature::MaxCost => self.max_cost as f64,
+            Feature::TotalWeight => self.total_weight as f64,
+            Feature::CapacityRatio => self.capacity_ratio,
+            Feature::Correlation => self.correlation,
+            Feature::WeightGcd => self.weight_gcd as f64,
+            Feature::CostGcd => self.cost_gcd as f64,
+            Feature::Balance => self.balance,
+            Feature::LpGap => self.lp_gap,
+            Feature::WeightCells => self.weight_cells as f64,
+            Feature::CostCells => self.cost_cells as f64,
+            Feature::CellsRatio => self.cost_cells as f64 / self.weight_cells.max(1) as f64,
+        }
+    }
+}
+
+impl fmt::Display for Features {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let values = FEATURES
+            .iter()
+            .map(|(name, feature)| format!("{}: {}", name, self.value(*feature)))
+            .collect::<Vec<_>>();
+        write!(f, "{}", values.join(" "))
+    }
+}
+
+fn correlation(problem: &Problem) -> f64 {
+    let n = problem.items.len() as f64;
+    let (sum_w, sum_c) = problem.items.iter().fold((0.0, 0.0), |(w, c), item| {
+        (w + item.weight as f64, c + item.cost as f64)
+    });
+    let (mean_w, mean_c) = (sum_w / n, sum_c / n);
+    let (cov, var_w, var_c) =
+        problem
+            .items
+            .iter()
+            .fold((0.0, 0.0, 0.0), |(cov, var_w, var_c), item| {
+                let dw = item.weight as f64 - mean_w;
+                let dc = item.cost as f64 - mean_c;
+                (cov + dw * dc, var_w + dw * dw, var_c + dc * dc)
+            });
+    if var_w > 0.0 && var_c > 0.0 {
+        cov / (var_w * var_c).sqrt()
+    } else {
+        0.0
+    }
+}
+
+fn balance(problem: &Problem) -> f64 {
+    let weights = || problem.items.iter().map(|item| item.weight as f64);
+    let min = weights().fold(f64::INFINITY, f64::min);
+    let max = weights().fold(f64::NEG_INFINITY, f64::max);
+    let mean = weights().sum::<f64>() / problem.items.len() as f64;
+    if max > min {
+        (2.0 * mean - min - max) / (max - min)
+    } else {
+        0.0
+    }
+}
+
+pub fn features(problem: &Problem) -> Features {
+    // tables are built only from items, which fit
+    let (mut items, _) = sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
+    let identity = (0..items.len()).collect::<Vec<_>>();
+    let (_, greedy_cost) =
+        construction_greedy_inner(&items, &identity, items.len(), problem.max_weight);
+    let lp = dantzig(items.iter(), problem.max_weight);
+    let weight_gcd = items.iter().fold(0, |acc, x| acc.gcd(x.weight)).max(1);
+    let cost_gcd = items.iter().fold(0, |acc, x| acc.gcd(x.cost)).max(1);
+    for item in &mut items {
+        item.cost /= cost_gcd;
+    }
+    let rows = items.len() as u64 + 1;
+    let total_weight = problem.items.iter().map(|item| item.weight as u64).sum();
+
+    Features {
+        n: problem.size,
+        capacity: problem.max_weight,
+        max_cost: problem
+            .items
+            .iter()
+            .map(|item| item.cost)
+            .max()
+            .unwrap_or(0),
+        total_weight,
+        capacity_ratio: problem.max_weight as f64 / (total_weight as f64).max(1.0),
+        correlation: correlation(problem),
+        weight_gcd,
+        cost_gcd,
+        balance: balance(problem),
+        lp_gap: if lp > 0 {
+            (lp - greedy_cost) as f64 / lp as f64
+        } else {
+            0.0
+        },
+        weight_cells: (problem.max_weight / weight_gcd) as u64 * rows + rows,
+        cost_cells: max_cost(&items, problem.max_weight) as u64 * rows + rows,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use super::*;
+
+    #[test]
+    fn features_of_small_problem() {
+        let problem = problem(0, 10, &[(2, 3), (4, 6), (6, 9)]);
+        let features = features(&problem);
+        assert_eq!(features.n, 3);
+        assert_eq!(features.total_weight, 12);
+        assert_eq!(features.max_cost, 9);
+        assert_eq!(features.weight_gcd, 2);
+        assert_eq!(features.cost_gcd, 3);
+        assert!((features.capacity_ratio - 10.0 / 12.0).abs() < 1e-9);
+        assert!((features.correlation - 1.0).abs() < 1e-9);
+        assert!(features.balance.abs() < 1e-9);
+        // capacity 10 / gcd 2 columns times 4 rows plus row of zero capacity
+        assert_eq!(features.weight_cells, 5 * 4 + 4);
+        assert!((0.0..=1.0).contains(&features.lp_gap));
+        assert_eq!(
+            features.value(Feature::CellsRatio),
+            features.cost_cells as f64 / 24.0
+        );
+    }
+
+    #[test]
+    fn inverse_and_degenerate_problems() {
+        let inverse = features(&problem(0, 10, &[(1, 9), (5, 5), (9, 1)]));
+        assert!((inverse.correlation + 1.0).abs() < 1e-9);
+        // lightest item is far from others
+        let heavy = features(&problem(0, 10, &[(1, 1), (9, 1), (9, 1)]));
+        assert!(heavy.balance > 0.0);
+        edge_problems().iter().for_each(|problem| {
+            let features = features(problem);
+            assert!(features.correlation.is_finite());
+            assert!(features.balance.is_finite());
+            assert!(features.lp_gap.is_finite());
+            assert!(features.value(Feature::CellsRatio).is_finite());
+        });
+    }
+
+    #[test]
+    fn parse_and_print_features() {
+        assert_eq!("n".parse::<Feature>().unwrap(), Feature::N);
+        assert_eq!(
+            "cells-ratio".parse::<Feature>().unwrap(),
+            Feature::CellsRatio
+        );
+        assert!("size".parse::<Feature>().is_err());
+        let printed = features(&random_problems(3, 1, 10)[0]).to_string();
+        // every feature is printed once as name: value
+        FEATURES.iter().for_each(|(name, _)| {
+            assert_eq!(
+                printed
+                    .split(' ')
+                    .filter(|part| *part == format!("{}:", name))
+                    .count(),
+                1,
+                "{}",
+                name
+            );
+        });
+    }
+}
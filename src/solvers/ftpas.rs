@@ -2,24 +2,56 @@ use super::{dynamic_cost::DynamicCostSolver, Item, Problem, Solution, SolverTrai
 
 #[derive(Debug, Clone)]
 pub struct FTPASSolver {
-    pub gcd: u32,
+    pub scaling: FTPASScaling,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum FTPASScaling {
+    // costs are divided by given number
+    Divisor(u32),
+    // divisor is derived from problem, so solution is at least (1 - epsilon) * optimum
+    Epsilon(f64),
+}
+
+impl FTPASSolver {
+    // Ibarra-Kim scaling K = epsilon * max cost / n, each of at most n items
+    // loses less than K by rounding, so error is below epsilon * max cost <= epsilon * optimum
+    pub fn divisor(&self, problem: &Problem) -> u32 {
+        match self.scaling {
+            FTPASScaling::Divisor(gcd) => gcd,
+            FTPASScaling::Epsilon(epsilon) => {
+                let (count, max_cost) = problem
+                    .items
+                    .iter()
+                    .filter(|item| item.weight <= problem.max_weight)
+                    .fold((0, 0), |(count, max_cost), item| {
+                        (count + 1, max_cost.max(item.cost))
+                    });
+                (epsilon * max_cost as f64 / count.max(1) as f64).max(1.0) as u32
+            }
+        }
+    }
+
+    // problem with costs divided by gcd, which is solved exactly
+    fn transform(&self, problem: &Problem) -> Problem {
+        let gcd = self.divisor(problem);
+        Problem {
+            items: problem
+                .items
+                .iter()
+                .map(|&item| Item {
+                    cost: item.cost / gcd,
+                    ..item
+                })
+                .collect(),
+            ..*problem
+        }
+    }
 }
 
 impl SolverTrait for FTPASSolver {
     fn construction(&self, problem: &Problem) -> Solution {
-        let transformed_items = problem
-            .items
-            .iter()
-            .map(|&item| Item {
-                cost: item.cost / self.gcd,
-                ..item
-            })
-            .collect();
-
-        let solution = DynamicCostSolver().construction(&Problem {
-            items: transformed_items,
-            ..*problem
-        });
+        let solution = DynamicCostSolver().construction(&self.transform(problem));
 
         Solution {
             cost: if let Some(ref items) = solution.items {
@@ -36,4 +68,50 @@ impl SolverTrait for FTPASSolver {
             ..solution
         }
     }
+
+    fn memory_estimate(&self, problem: &Problem) -> usize {
+        DynamicCostSolver().memory_estimate(&self.transform(problem))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_utils::*, utils::calculate_practical_ftpas_error};
+    use super::*;
+
+    #[test]
+    fn unit_divisor_is_exact() {
+        assert_exact(&FTPASSolver {
+            scaling: FTPASScaling::Divisor(1),
+        });
+    }
+
+    #[test]
+    fn error_is_within_practical_bound() {
+        for gcd in &[2, 5, 20] {
+            let solver = FTPASSolver {
+                scaling: FTPASScaling::Divisor(*gcd),
+            };
+            for problem in edge_problems().iter().chain(&random_problems(5, 20, 15)) {
+                let solution = solver.construction(problem);
+                check_solution(problem, &solution);
+                let error = optimum(problem) - solution.cost;
+                assert!(error as u64 <= calculate_practical_ftpas_error(problem, *gcd));
+            }
+        }
+    }
+
+    #[test]
+    fn epsilon_guarantees_relative_error() {
+        for &epsilon in &[0.1, 0.5, 0.9] {
+            let solver = FTPASSolver {
+                scaling: FTPASScaling::Epsilon(epsilon),
+            };
+            for problem in &random_problems(6, 20, 15) {
+                let solution = solver.construction(problem);
+                check_solution(problem, &solution);
+                assert!(solution.cost as f64 >= (1.0 - epsilon) * optimum(problem) as f64);
+            }
+        }
+    }
 }
@@ -0,0 +1,221 @@
+use super::{
+    sort_by_cost_weight_ratio, utils::seeded_rng, DisplayError, Item, Problem, Solution,
+    SolverTrait,
+};
+
+use rand::Rng;
+use std::str::FromStr;
+
+#[derive(Debug, Clone)]
+pub struct GeneticSolver {
+    pub population: usize,
+    pub generations: usize,
+    // probability of flipping each gene, when not set 1 / n is used
+    pub mutation_rate: Option<f64>,
+    pub crossover: Crossover,
+    // number of best individuals copied into next generation
+    pub elitism: usize,
+    pub tournament_size: usize,
+    pub seed: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Crossover {
+    Uniform,
+    OnePoint,
+}
+
+impl FromStr for Crossover {
+    type Err = DisplayError;
+    fn from_str(name: &str) -> Result<Crossover, DisplayError> {
+        match name {
+            "uniform" => Ok(Crossover::Uniform),
+            "one-point" => Ok(Crossover::OnePoint),
+            _ => Err(format!(
+                "Crossover {:?} not found, following are valid: uniform, one-point.",
+                name
+            )
+            .into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Individual {
+    genes: Vec<bool>,
+    cost: u32,
+}
+
+// items are sorted by cost/weight ratio, so worst items are removed from the end,
+// until it fits and then the best items, which fit, are added from the start
+fn repair(mut genes: Vec<bool>, items: &[Item], max_weight: u32) -> Individual {
+    let (mut cost, mut weight) = genes
+        .iter()
+        .zip(items.iter())
+        .filter(|(&used, _)| used)
+        .fold((0u32, 0u64), |(cost, weight), (_, item)| {
+            (cost + item.cost, weight + item.weight as u64)
+        });
+    for (gene, item) in genes.iter_mut().zip(items.iter()).rev() {
+        if weight <= max_weight as u64 {
+            break;
+        }
+        if *gene {
+            *gene = false;
+            cost -= item.cost;
+            weight -= item.weight as u64;
+        }
+    }
+    for (gene, item) in genes.iter_mut().zip(items.iter()) {
+        if !*gene && weight + item.weight as u64 <= max_weight as u64 {
+            *gene = true;
+            cost += item.cost;
+            weight += item.weight as u64;
+        }
+    }
+    Individual { genes, cost }
+}
+
+// best of randomly picked individuals
+fn tournament<'a, R: Rng>(
+    population: &'a [Individual],
+    size: usize,
+    rng: &mut R,
+) -> &'a Individual {
+    (0..size.max(1))
+        .map(|_| &population[rng.gen_range(0, population.len())])
+        .max_by_key(|individual| individual.cost)
+        .unwrap()
+}
+
+impl SolverTrait for GeneticSolver {
+    fn construction(&self, problem: &Problem) -> Solution {
+        let (items, mapping) = sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
+
+        if items.is_empty() || self.population == 0 {
+            return Solution::empty(problem.id, problem.size);
+        }
+
+        let mut rng = seeded_rng(self.seed, problem);
+
+        let mutation_rate = self.mutation_rate.unwrap_or(1.0 / items.len() as f64);
+
+        let mut population = (0..self.population)
+            .map(|_| {
+                repair(
+                    (0..items.len()).map(|_| rng.gen()).collect(),
+                    &items,
+                    problem.max_weight,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        for _ in 0..self.generations {
+            population.sort_by_key(|individual| std::cmp::Reverse(individual.cost));
+
+            let offspring = (self.elitism.min(self.population)..self.population)
+                .map(|_| {
+                    let mother = tournament(&population, self.tournament_size, &mut rng);
+                    let father = tournament(&population, self.tournament_size, &mut rng);
+                    let point = rng.gen_range(0, items.len());
+                    let genes = mother
+                        .genes
+                        .iter()
+                        .zip(father.genes.iter())
+                        .enumerate()
+                        .map(|(i, (&m, &f))| {
+                            let gene = match self.crossover {
+                                Crossover::Uniform => {
+                                    if rng.gen() {
+                                        m
+                                    } else {
+                                        f
+                                    }
+                                }
+                                Crossover::OnePoint => {
+                                    if i < point {
+                                        m
+                                    } else {
+                                        f
+                                    }
+                                }
+                            };
+                            gene ^ (rng.gen::<f64>() < mutation_rate)
+                        })
+                        .collect();
+                    repair(genes, &items, problem.max_weight)
+                })
+                .collect::<Vec<_>>();
+
+            population.truncate(self.elitism);
+            population.extend(offspring);
+        }
+
+        let best = population
+            .into_iter()
+            .max_by_key(|individual| individual.cost)
+            .unwrap();
+
+        Solution {
+            id: problem.id,
+            size: problem.size,
+            cost: best.cost,
+            items: Some(best.genes.into_iter().enumerate().fold(
+                vec![false; problem.size],
+                |mut acc, (i, x)| {
+                    acc[mapping[i]] = x;
+                    acc
+                },
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use super::*;
+
+    #[test]
+    fn repair_fits_and_fills() {
+        let problem = problem(0, 10, &[(4, 8), (3, 5), (5, 6), (6, 6)]);
+        let (items, _) = sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
+        let repaired = repair(vec![true; 4], &items, problem.max_weight);
+        assert_eq!(repaired.genes, vec![true, true, false, false]);
+        assert_eq!(repaired.cost, 13);
+        let filled = repair(vec![false, false, false, true], &items, problem.max_weight);
+        assert_eq!(filled.genes, vec![true, false, false, true]);
+        assert_eq!(filled.cost, 14);
+    }
+
+    #[test]
+    fn both_crossovers_fit() {
+        for &crossover in &[Crossover::Uniform, Crossover::OnePoint] {
+            assert_feasible(&GeneticSolver {
+                population: 20,
+                generations: 30,
+                mutation_rate: None,
+                crossover,
+                elitism: 2,
+                tournament_size: 3,
+                seed: 42,
+            });
+        }
+    }
+
+    #[test]
+    fn empty_population_gives_empty_solution() {
+        let problem = &random_problems(1, 1, 10)[0];
+        let solution = GeneticSolver {
+            population: 0,
+            generations: 10,
+            mutation_rate: None,
+            crossover: Crossover::Uniform,
+            elitism: 0,
+            tournament_size: 3,
+            seed: 42,
+        }
+        .construction(problem);
+        assert_eq!(solution, Solution::empty(problem.id, problem.size));
+    }
+}
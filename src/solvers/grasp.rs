@@ -0,0 +1,158 @@
+use super::{
+    neighbourhood::{local_search, repair},
+    sort_by_cost_weight_ratio,
+    utils::seeded_rng,
+    Problem, Solution, SolverTrait,
+};
+
+use rand::Rng;
+
+// randomized greedy construction followed by local search
+#[derive(Debug, Clone)]
+pub struct GraspSolver {
+    pub iterations: usize,
+    // how many best fitting items by cost/weight ratio are candidates for next pick
+    pub rcl_size: usize,
+    pub double_swap: bool,
+    pub seed: u64,
+}
+
+// local search restarted from perturbed local optimum
+#[derive(Debug, Clone)]
+pub struct IteratedLocalSearchSolver {
+    pub iterations: usize,
+    // number of random flips between local searches
+    pub perturbation: usize,
+    pub double_swap: bool,
+    pub seed: u64,
+}
+
+fn map_solution(problem: &Problem, state: Vec<bool>, mapping: &[usize]) -> Vec<bool> {
+    state
+        .into_iter()
+        .enumerate()
+        .fold(vec![false; problem.size], |mut acc, (i, x)| {
+            acc[mapping[i]] = x;
+            acc
+        })
+}
+
+impl SolverTrait for GraspSolver {
+    fn construction(&self, problem: &Problem) -> Solution {
+        let (items, mapping) = sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
+
+        if items.is_empty() {
+            return Solution::empty(problem.id, problem.size);
+        }
+
+        let mut rng = seeded_rng(self.seed, problem);
+
+        let (best_solution, best_cost) = (0..self.iterations)
+            .map(|_| {
+                let mut state = vec![false; items.len()];
+                let mut rem_weight = problem.max_weight;
+                loop {
+                    // items are sorted by ratio, so restricted candidate list is prefix of them
+                    let candidates = (0..items.len())
+                        .filter(|&i| !state[i] && items[i].weight <= rem_weight)
+                        .take(self.rcl_size.max(1))
+                        .collect::<Vec<_>>();
+                    if candidates.is_empty() {
+                        break;
+                    }
+                    let picked = candidates[rng.gen_range(0, candidates.len())];
+                    state[picked] = true;
+                    rem_weight -= items[picked].weight;
+                }
+                let (cost, _) =
+                    local_search(&mut state, &items, problem.max_weight, self.double_swap);
+                (state, cost)
+            })
+            .max_by_key(|(_, cost)| *cost)
+            .unwrap_or_else(|| (vec![false; items.len()], 0));
+
+        Solution {
+            id: problem.id,
+            size: problem.size,
+            cost: best_cost,
+            items: Some(map_solution(problem, best_solution, &mapping)),
+        }
+    }
+}
+
+impl SolverTrait for IteratedLocalSearchSolver {
+    fn construction(&self, problem: &Problem) -> Solution {
+        let (items, mapping) = sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
+
+        if items.is_empty() {
+            return Solution::empty(problem.id, problem.size);
+        }
+
+        let mut rng = seeded_rng(self.seed, problem);
+
+        // empty knapsack is fitting, local search fills it greedily
+        let mut state = vec![false; items.len()];
+        let (mut cost, _) = local_search(&mut state, &items, problem.max_weight, self.double_swap);
+        let mut best_solution = state.clone();
+        let mut best_cost = cost;
+
+        let mut candidate = state.clone();
+        for _ in 0..self.iterations {
+            candidate.copy_from_slice(&state);
+            for _ in 0..self.perturbation {
+                let i = rng.gen_range(0, items.len());
+                candidate[i] = !candidate[i];
+            }
+            repair(&mut candidate, &items, problem.max_weight);
+            let (candidate_cost, _) =
+                local_search(&mut candidate, &items, problem.max_weight, self.double_swap);
+
+            // accepting equal cost lets search move over plateaus
+            if candidate_cost >= cost {
+                state.copy_from_slice(&candidate);
+                cost = candidate_cost;
+            }
+            if cost > best_cost {
+                best_solution.copy_from_slice(&state);
+                best_cost = cost;
+            }
+        }
+
+        Solution {
+            id: problem.id,
+            size: problem.size,
+            cost: best_cost,
+            items: Some(map_solution(problem, best_solution, &mapping)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use super::*;
+
+    #[test]
+    fn grasp_fits() {
+        for &double_swap in &[false, true] {
+            assert_feasible(&GraspSolver {
+                iterations: 10,
+                rcl_size: 3,
+                double_swap,
+                seed: 42,
+            });
+        }
+    }
+
+    #[test]
+    fn iterated_local_search_fits() {
+        for &double_swap in &[false, true] {
+            assert_feasible(&IteratedLocalSearchSolver {
+                iterations: 20,
+                perturbation: 3,
+                double_swap,
+                seed: 42,
+            });
+        }
+    }
+}
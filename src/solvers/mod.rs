@@ -1,11 +1,20 @@
 pub mod utils;
 pub use utils::*;
 
+#[cfg(test)]
+mod test_utils;
+
 mod naive;
 use naive::NaiveSolver;
 
+mod bounds;
+pub use bounds::UpperBound;
+
 mod pruning;
-use pruning::PruningSolver;
+pub use pruning::PruningSolver;
+
+mod parallel_pruning;
+use parallel_pruning::ParallelPruningSolver;
 
 mod dynamic_weight;
 use dynamic_weight::DynamicWeightSolver;
@@ -13,8 +22,23 @@ use dynamic_weight::DynamicWeightSolver;
 mod dynamic_cost;
 use dynamic_cost::DynamicCostSolver;
 
+mod dynamic_linear;
+use dynamic_linear::{DynamicCostLinearSolver, DynamicWeightLinearSolver};
+
+mod dynamic_sparse;
+use dynamic_sparse::DynamicSparseSolver;
+
+mod dynamic_simd;
+use dynamic_simd::DynamicWeightSimdSolver;
+
+mod dynamic_parallel;
+use dynamic_parallel::DynamicWeightParallelSolver;
+
 mod ftpas;
-use ftpas::FTPASSolver;
+pub use ftpas::{FTPASScaling, FTPASSolver};
+
+mod ptas;
+use ptas::PTASSolver;
 
 mod greedy;
 use greedy::GreedySolver;
@@ -22,17 +46,52 @@ use greedy::GreedySolver;
 mod redux;
 use redux::ReduxSolver;
 
+mod beam;
+use beam::{BeamSearchSolver, LimitedDiscrepancySolver};
+
+mod neighbourhood;
+
+mod polish;
+pub use polish::polish;
+
+mod reduction;
+pub use reduction::reduce;
+
+mod grasp;
+use grasp::{GraspSolver, IteratedLocalSearchSolver};
+
 mod tabusearch;
 use tabusearch::TabuSearchSolver;
+pub use tabusearch::{InitialState, Restart};
+
+mod annealing;
+use annealing::SimulatedAnnealingSolver;
+pub use annealing::{Cooling, Overweight};
+
+mod genetic;
+pub use genetic::Crossover;
+use genetic::GeneticSolver;
+
+mod ant_colony;
+use ant_colony::AntColonySolver;
 
 mod approx_pruning;
-use approx_pruning::ApproxPruningSolver;
+pub use approx_pruning::ApproxPruningSolver;
+
+mod portfolio;
+use portfolio::PortfolioSolver;
+
+pub mod features;
+
+mod auto;
+use auto::AutoSolver;
+pub use auto::{AutoRule, DEFAULT_RULES};
 
 use enum_dispatch::enum_dispatch;
 
 pub use super::Opts;
 
-use super::DisplayError;
+use super::{DisplayError, MemorySize};
 pub use super::{Item, Problem, Solution};
 
 use num_rational::Ratio;
@@ -42,49 +101,111 @@ pub type ratio = Ratio<u32>;
 
 #[enum_dispatch]
 #[derive(Debug, Clone)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum Solver {
     Naive(NaiveSolver),
     Pruning(PruningSolver),
+    ParallelPruning(ParallelPruningSolver),
     DynamicWeight(DynamicWeightSolver),
     DynamicCost(DynamicCostSolver),
+    DynamicSparse(DynamicSparseSolver),
+    DynamicWeightLinear(DynamicWeightLinearSolver),
+    DynamicCostLinear(DynamicCostLinearSolver),
+    DynamicWeightParallel(DynamicWeightParallelSolver),
+    DynamicWeightSimd(DynamicWeightSimdSolver),
     Greedy(GreedySolver),
     Redux(ReduxSolver),
     FTPAS(FTPASSolver),
+    PTAS(PTASSolver),
     TabuSearch(TabuSearchSolver),
+    SimulatedAnnealing(SimulatedAnnealingSolver),
+    Genetic(GeneticSolver),
+    AntColony(AntColonySolver),
+    Grasp(GraspSolver),
+    IteratedLocalSearch(IteratedLocalSearchSolver),
     ApproxPruning(ApproxPruningSolver),
+    BeamSearch(BeamSearchSolver),
+    LimitedDiscrepancy(LimitedDiscrepancySolver),
+    Portfolio(PortfolioSolver),
+    Auto(AutoSolver),
 }
 pub use Solver::*;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum Methods {
     Naive,
     Pruning,
+    ParallelPruning,
     DynamicWeight,
     DynamicCost,
+    DynamicSparse,
+    DynamicWeightLinear,
+    DynamicCostLinear,
+    DynamicWeightParallel,
+    DynamicWeightSimd,
     Greedy,
     Redux,
     FTPAS,
+    PTAS,
     TabuSearch,
+    SimulatedAnnealing,
+    Genetic,
+    AntColony,
+    Grasp,
+    IteratedLocalSearch,
     ApproxPruning,
+    BeamSearch,
+    LimitedDiscrepancy,
+    Portfolio,
+    Auto,
 }
 
 use itertools::Itertools;
+use rayon::ThreadPool;
+use std::fmt;
 use std::str::FromStr;
+use std::sync::{atomic::AtomicBool, Arc, Mutex};
+
+pub const METHODS: [(&str, Methods); 25] = [
+    ("naive", Methods::Naive),
+    ("pruning", Methods::Pruning),
+    ("parallel-pruning", Methods::ParallelPruning),
+    ("dynamic-weight", Methods::DynamicWeight),
+    ("dynamic-cost", Methods::DynamicCost),
+    ("dynamic-sparse", Methods::DynamicSparse),
+    ("dynamic-weight-linear", Methods::DynamicWeightLinear),
+    ("dynamic-cost-linear", Methods::DynamicCostLinear),
+    ("dynamic-weight-parallel", Methods::DynamicWeightParallel),
+    ("dynamic-weight-simd", Methods::DynamicWeightSimd),
+    ("greedy", Methods::Greedy),
+    ("redux", Methods::Redux),
+    ("ftpas", Methods::FTPAS),
+    ("ptas", Methods::PTAS),
+    ("tabu-search", Methods::TabuSearch),
+    ("simulated-annealing", Methods::SimulatedAnnealing),
+    ("genetic", Methods::Genetic),
+    ("ant-colony", Methods::AntColony),
+    ("grasp", Methods::Grasp),
+    ("iterated-local-search", Methods::IteratedLocalSearch),
+    ("approx-pruning", Methods::ApproxPruning),
+    ("beam-search", Methods::BeamSearch),
+    ("limited-discrepancy", Methods::LimitedDiscrepancy),
+    ("portfolio", Methods::Portfolio),
+    ("auto", Methods::Auto),
+];
+
+impl fmt::Display for Methods {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = METHODS.iter().find(|(_, method)| method == self).unwrap().0;
+        write!(f, "{}", name)
+    }
+}
 
 impl FromStr for Methods {
     type Err = DisplayError;
     fn from_str(name: &str) -> Result<Methods, DisplayError> {
-        let methods = [
-            ("naive", Self::Naive),
-            ("pruning", Self::Pruning),
-            ("dynamic-weight", Self::DynamicWeight),
-            ("dynamic-cost", Self::DynamicCost),
-            ("greedy", Self::Greedy),
-            ("redux", Self::Redux),
-            ("ftpas", Self::FTPAS),
-            ("tabu-search", Self::TabuSearch),
-            ("approx-pruning", Self::ApproxPruning),
-        ];
+        let methods = &METHODS;
         methods
             .iter()
             .map(|(method_name, method)| {
@@ -119,31 +240,153 @@ pub trait SolverTrait {
             Solution::none(problem.id, problem.size)
         }
     }
+    // construction, which stops and returns None soon after cancel is set,
+    // solvers without support of cancellation always finish
+    fn cancellable_construction(
+        &self,
+        problem: &Problem,
+        _cancel: &AtomicBool,
+    ) -> Option<Solution> {
+        Some(self.construction(problem))
+    }
+    // bytes of the biggest table solver allocates, zero if it is negligible
+    fn memory_estimate(&self, _problem: &Problem) -> usize {
+        0
+    }
 }
 
 impl Solver {
+    pub fn is_randomized(&self) -> bool {
+        match self {
+            Auto(auto) => auto
+                .solvers
+                .iter()
+                .any(|(_, solver)| solver.is_randomized()),
+            _ => matches!(
+                self,
+                TabuSearch(_)
+                    | SimulatedAnnealing(_)
+                    | Genetic(_)
+                    | AntColony(_)
+                    | Grasp(_)
+                    | IteratedLocalSearch(_)
+            ),
+        }
+    }
+
     pub fn is_exact(&self) -> bool {
         match self {
-            Naive(_) | Pruning(_) | DynamicWeight(_) | DynamicCost(_) => true,
-            Greedy(_) | Redux(_) | FTPAS(_) | ApproxPruning(_) | TabuSearch(_) => false,
+            Naive(_)
+            | Pruning(_)
+            | ParallelPruning(_)
+            | DynamicWeight(_)
+            | DynamicCost(_)
+            | DynamicSparse(_)
+            | DynamicWeightLinear(_)
+            | DynamicCostLinear(_)
+            | DynamicWeightParallel(_)
+            | DynamicWeightSimd(_)
+            | Portfolio(_) => true,
+            Greedy(_)
+            | Redux(_)
+            | FTPAS(_)
+            | PTAS(_)
+            | ApproxPruning(_)
+            | BeamSearch(_)
+            | LimitedDiscrepancy(_)
+            | TabuSearch(_)
+            | SimulatedAnnealing(_)
+            | Genetic(_)
+            | AntColony(_)
+            | Grasp(_)
+            | IteratedLocalSearch(_) => false,
+            Auto(auto) => auto.solvers.iter().all(|(_, solver)| solver.is_exact()),
+        }
+    }
+
+    // cancellable_construction stops soon after cancel is set
+    pub fn is_cancellable(&self) -> bool {
+        match self {
+            Pruning(_) | DynamicWeight(_) | DynamicCost(_) | DynamicSparse(_) => true,
+            Auto(auto) => auto
+                .solvers
+                .iter()
+                .all(|(_, solver)| solver.is_cancellable()),
+            _ => false,
+        }
+    }
+
+    // Checks --memory-limit before any table of problem is allocated. Problem over it is
+    // an error, or with --memory-fallback is solved by fallback, second value tells that.
+    pub fn within_memory_limit<'a>(
+        &'a self,
+        fallback: &'a Solver,
+        problem: &Problem,
+        opts: &Opts,
+    ) -> Result<(&'a Solver, bool), DisplayError> {
+        match opts.memory_limit {
+            Some(MemorySize(limit)) if self.memory_estimate(problem) > limit => {
+                if opts.memory_fallback {
+                    Ok((fallback, true))
+                } else {
+                    Err(format!(
+                        "Problem {} needs {} bytes, which is over memory limit {} bytes. Use --memory-fallback to solve it by pruning.",
+                        problem.id,
+                        self.memory_estimate(problem),
+                        limit
+                    )
+                    .into())
+                }
+            }
+            _ => Ok((self, false)),
         }
     }
 
+    // solver of problems over memory limit
+    pub fn memory_fallback(opts: &Opts) -> Solver {
+        Pruning(PruningSolver { bound: opts.bound })
+    }
+
     pub fn from_opts(opts: &Opts) -> Result<Solver, DisplayError> {
-        Ok(match opts.method {
+        match opts.method {
+            Some(method) => Self::from_method(method, opts),
+            None => Err("Missing method.".into()),
+        }
+    }
+
+    pub fn from_method(method: Methods, opts: &Opts) -> Result<Solver, DisplayError> {
+        // seed is drawn in main, so all methods of run share it
+        let seed = opts.seed.unwrap_or_default();
+        Ok(match method {
             Methods::Naive => Naive(NaiveSolver()),
-            Methods::Pruning => Pruning(PruningSolver()),
+            Methods::Pruning => Pruning(PruningSolver { bound: opts.bound }),
+            Methods::ParallelPruning => ParallelPruning(ParallelPruningSolver {
+                pool: thread_pool(opts)?,
+                bound: opts.bound,
+            }),
             Methods::DynamicWeight => DynamicWeight(DynamicWeightSolver()),
             Methods::DynamicCost => DynamicCost(DynamicCostSolver()),
+            Methods::DynamicSparse => DynamicSparse(DynamicSparseSolver()),
+            Methods::DynamicWeightLinear => DynamicWeightLinear(DynamicWeightLinearSolver()),
+            Methods::DynamicCostLinear => DynamicCostLinear(DynamicCostLinearSolver()),
+            Methods::DynamicWeightSimd => DynamicWeightSimd(DynamicWeightSimdSolver()),
+            Methods::DynamicWeightParallel => DynamicWeightParallel(DynamicWeightParallelSolver {
+                pool: thread_pool(opts)?,
+            }),
             Methods::Greedy => Greedy(GreedySolver()),
             Methods::Redux => Redux(ReduxSolver()),
             Methods::FTPAS => FTPAS(FTPASSolver {
-                gcd: if let Some(p) = opts.precision {
-                    p
-                } else {
-                    return Err("Missing precision option.".into());
+                scaling: match (opts.precision, opts.epsilon) {
+                    (Some(0), None) => return Err("Precision has to be positive.".into()),
+                    (Some(p), None) => FTPASScaling::Divisor(p),
+                    (None, Some(_)) => FTPASScaling::Epsilon(epsilon(opts)?),
+                    (Some(_), Some(_)) => {
+                        return Err("Precision and epsilon options are exclusive.".into())
+                    }
+                    (None, None) => return Err("Missing precision or epsilon option.".into()),
                 },
             }),
+            Methods::PTAS => PTAS(PTASSolver::from_epsilon(epsilon(opts)?)),
             Methods::ApproxPruning => ApproxPruning(ApproxPruningSolver {
                 precision: if let Some(p) = opts.precision {
                     p
@@ -151,6 +394,61 @@ impl Solver {
                     return Err("Missing precision option.".into());
                 },
             }),
+            Methods::BeamSearch => BeamSearch(BeamSearchSolver {
+                width: if let Some(w) = opts.beam_width {
+                    w
+                } else {
+                    return Err("Missing beam width option.".into());
+                },
+                bound: opts.bound,
+            }),
+            Methods::LimitedDiscrepancy => LimitedDiscrepancy(LimitedDiscrepancySolver {
+                discrepancies: if let Some(d) = opts.discrepancies {
+                    d
+                } else {
+                    return Err("Missing discrepancies option.".into());
+                },
+                bound: opts.bound,
+            }),
+            Methods::Portfolio => Portfolio(PortfolioSolver {
+                solvers: opts
+                    .portfolio
+                    .iter()
+                    .map(|&method| {
+                        let solver = match method {
+                            Methods::Portfolio | Methods::Auto => {
+                                return Err("Portfolio can't contain portfolio or auto.".into())
+                            }
+                            _ => Self::from_method(method, opts)?,
+                        };
+                        if !solver.is_exact() {
+                            Err(format!("Portfolio method {} is not exact.", method).into())
+                        } else if !solver.is_cancellable() {
+                            Err(format!("Portfolio method {} can't be cancelled.", method).into())
+                        } else {
+                            Ok((method, solver))
+                        }
+                    })
+                    .collect::<Result<_, DisplayError>>()?,
+                winner: Arc::new(Mutex::new(None)),
+            }),
+            Methods::Auto => {
+                match opts.auto_rules.last() {
+                    Some(rule) if rule.condition.is_none() => (),
+                    _ => return Err("Last auto rule has to be without condition.".into()),
+                }
+                let methods = opts.auto_rules.iter().map(|rule| rule.method).unique();
+                Auto(AutoSolver {
+                    rules: opts.auto_rules.clone(),
+                    solvers: methods
+                        .map(|method| match method {
+                            Methods::Auto => Err("Auto rules can't contain auto.".into()),
+                            _ => Ok((method, Self::from_method(method, opts)?)),
+                        })
+                        .collect::<Result<_, DisplayError>>()?,
+                    chosen: Arc::new(Mutex::new(None)),
+                })
+            }
             Methods::TabuSearch => TabuSearch(TabuSearchSolver {
                 memory_size: if let Some(m) = opts.memory_size {
                     m
@@ -162,7 +460,194 @@ impl Solver {
                 } else {
                     return Err("Missing iterations option.".into());
                 },
+                initial: opts.initial,
+                restart: opts.restart,
+                restart_after: opts.restart_after,
+                seed,
+            }),
+            Methods::SimulatedAnnealing => SimulatedAnnealing(SimulatedAnnealingSolver {
+                iterations: if let Some(i) = opts.iterations {
+                    i
+                } else {
+                    return Err("Missing iterations option.".into());
+                },
+                temperature: opts.temperature,
+                cooling: opts.cooling,
+                cooling_factor: opts.cooling_factor,
+                overweight: opts.overweight,
+                seed,
+            }),
+            Methods::Genetic => Genetic(GeneticSolver {
+                population: if let Some(p) = opts.population {
+                    p
+                } else {
+                    return Err("Missing population option.".into());
+                },
+                generations: if let Some(g) = opts.generations {
+                    g
+                } else {
+                    return Err("Missing generations option.".into());
+                },
+                mutation_rate: opts.mutation_rate,
+                crossover: opts.crossover,
+                elitism: opts.elitism,
+                tournament_size: opts.tournament_size,
+                seed,
+            }),
+            Methods::AntColony => AntColony(AntColonySolver {
+                colony_size: if let Some(c) = opts.colony_size {
+                    c
+                } else {
+                    return Err("Missing colony size option.".into());
+                },
+                iterations: if let Some(i) = opts.iterations {
+                    i
+                } else {
+                    return Err("Missing iterations option.".into());
+                },
+                evaporation: opts.evaporation,
+                alpha: opts.alpha,
+                beta: opts.beta,
+                seed,
+            }),
+            Methods::Grasp => Grasp(GraspSolver {
+                iterations: if let Some(i) = opts.iterations {
+                    i
+                } else {
+                    return Err("Missing iterations option.".into());
+                },
+                rcl_size: opts.rcl_size,
+                double_swap: opts.double_swap,
+                seed,
+            }),
+            Methods::IteratedLocalSearch => IteratedLocalSearch(IteratedLocalSearchSolver {
+                iterations: if let Some(i) = opts.iterations {
+                    i
+                } else {
+                    return Err("Missing iterations option.".into());
+                },
+                perturbation: opts.perturbation,
+                double_swap: opts.double_swap,
+                seed,
             }),
         })
     }
 }
+
+fn epsilon(opts: &Opts) -> Result<f64, DisplayError> {
+    match opts.epsilon {
+        Some(e) if e > 0.0 && e < 1.0 => Ok(e),
+        Some(_) => Err("Epsilon has to be in (0, 1).".into()),
+        None => Err("Missing epsilon option.".into()),
+    }
+}
+
+fn thread_pool(opts: &Opts) -> Result<Arc<ThreadPool>, DisplayError> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(opts.threads.unwrap_or(0))
+        .build()
+        .map(Arc::new)
+        .map_err(|e| format!("Thread pool can't be created: {}", e).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_utils::*;
+    use super::*;
+    use structopt::StructOpt;
+
+    fn opts(args: &[&str]) -> Opts {
+        Opts::from_iter_safe(["knapsack"].iter().chain(args)).unwrap()
+    }
+
+    #[test]
+    fn every_method_name_parses_as_method() {
+        // genetic is close to subcommand generate, which clap took for misspelling
+        METHODS.iter().for_each(|(name, method)| {
+            let opts = Opts::from_iter_safe(&["knapsack", name]).unwrap();
+            assert_eq!(opts.method, Some(*method), "{}", name);
+            assert!(opts.command.is_none(), "{}", name);
+        });
+    }
+
+    #[test]
+    fn memory_limit_falls_back_to_pruning() {
+        let problem = &random_problems(4, 1, 20)[0];
+        let solver = Solver::from_method(Methods::DynamicWeight, &opts(&[])).unwrap();
+        let fallback = Solver::memory_fallback(&opts(&[]));
+        let estimate = solver.memory_estimate(problem);
+        let within = |args: &[&str]| {
+            solver
+                .within_memory_limit(&fallback, problem, &opts(args))
+                .map(|(_, fell_back)| fell_back)
+                .ok()
+        };
+        assert_eq!(within(&[]), Some(false));
+        assert_eq!(
+            within(&[&format!("--memory-limit={}", estimate)]),
+            Some(false)
+        );
+        let limit = format!("--memory-limit={}", estimate - 1);
+        assert_eq!(within(&[&limit]), None);
+        assert_eq!(within(&[&limit, "--memory-fallback"]), Some(true));
+    }
+
+    #[test]
+    fn methods_share_seed_of_opts() {
+        // solvers built separately, like by bench, auto and portfolio, have same seed
+        let problems = random_problems(5, 5, 20);
+        for args in &[vec!["--seed=11"], vec![]] {
+            let opts = opts(&[&["--generations=5", "--population=10"], &args[..]].concat());
+            let solutions = || {
+                let solver = Solver::from_method(Methods::Genetic, &opts).unwrap();
+                problems
+                    .iter()
+                    .map(|problem| solver.construction(problem))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(solutions(), solutions());
+        }
+    }
+
+    #[test]
+    fn missing_method_is_error() {
+        assert!(Solver::from_opts(&opts(&[])).is_err());
+        assert!(Solver::from_opts(&opts(&["pruning"])).is_ok());
+    }
+
+    #[test]
+    fn seeded_solvers_do_not_depend_on_order_of_problems() {
+        let opts = opts(&[
+            "--seed=7",
+            "--iterations=50",
+            "--memory-size=5",
+            "--population=10",
+            "--generations=10",
+            "--colony-size=5",
+        ]);
+        let problems = random_problems(8, 10, 15);
+        for &method in &[
+            Methods::TabuSearch,
+            Methods::SimulatedAnnealing,
+            Methods::Genetic,
+            Methods::AntColony,
+            Methods::Grasp,
+            Methods::IteratedLocalSearch,
+        ] {
+            let solver = Solver::from_method(method, &opts).unwrap();
+            assert!(solver.is_randomized());
+            let forward = problems
+                .iter()
+                .map(|problem| solver.construction(problem))
+                .collect::<Vec<_>>();
+            let solver = Solver::from_method(method, &opts).unwrap();
+            let mut backward = problems
+                .iter()
+                .rev()
+                .map(|problem| solver.construction(problem))
+                .collect::<Vec<_>>();
+            backward.reverse();
+            assert_eq!(forward, backward, "method {}", method);
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use super::Item;
+
+use arrayvec::ArrayVec;
+use itertools::{iproduct, Itertools};
+
+// moves of local search over state, where state[i] says if items[i] is in knapsack
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Move {
+    Flip(usize),
+    // (removed, added)
+    Swap(usize, usize),
+    // two removed and two added items
+    DoubleSwap((usize, usize), (usize, usize)),
+    // two removed, one added
+    TwoForOne((usize, usize), usize),
+    // one removed, two added
+    OneForTwo(usize, (usize, usize)),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Neighbourhood {
+    Flip,
+    Swap,
+    DoubleSwap,
+    TwoForOne,
+    OneForTwo,
+}
+
+impl Move {
+    pub fn indices(&self) -> ArrayVec<[usize; 4]> {
+        let mut indices = ArrayVec::new();
+        match *self {
+            Move::Flip(i) => indices.push(i),
+            Move::Swap(out, into) => {
+                indices.push(out);
+                indices.push(into);
+            }
+            Move::DoubleSwap((out1, out2), (into1, into2)) => {
+                indices.push(out1);
+                indices.push(out2);
+                indices.push(into1);
+                indices.push(into2);
+            }
+            Move::TwoForOne((out1, out2), into) => {
+                indices.push(out1);
+                indices.push(out2);
+                indices.push(into);
+            }
+            Move::OneForTwo(out, (into1, into2)) => {
+                indices.push(out);
+                indices.push(into1);
+                indices.push(into2);
+            }
+        }
+        indices
+    }
+
+    // (cost, weight) of state after the move
+    pub fn evaluate(&self, state: &[bool], items: &[Item], cost: u32, weight: u32) -> (u32, u32) {
+        self.indices()
+            .iter()
+            .fold((cost, weight), |(cost, weight), &i| {
+                if state[i] {
+                    (cost - items[i].cost, weight - items[i].weight)
+                } else {
+                    (cost + items[i].cost, weight + items[i].weight)
+                }
+            })
+    }
+
+    pub fn apply(&self, state: &mut [bool]) {
+        self.indices().iter().for_each(|&i| state[i] = !state[i]);
+    }
+}
+
+pub fn cost_weight(state: &[bool], items: &[Item]) -> (u32, u32) {
+    state
+        .iter()
+        .zip(items.iter())
+        .filter(|(&in_pack, _)| in_pack)
+        .fold((0, 0), |(cost, weight), (_, item)| {
+            (cost + item.cost, weight + item.weight)
+        })
+}
+
+// (indices of items in knapsack, indices of items out of knapsack)
+fn partition(state: &[bool]) -> (Vec<usize>, Vec<usize>) {
+    (0..state.len()).partition(|&i| state[i])
+}
+
+pub fn flips(size: usize) -> impl Iterator<Item = Move> {
+    (0..size).map(Move::Flip)
+}
+
+pub fn swaps(state: &[bool]) -> impl Iterator<Item = Move> {
+    let (inside, outside) = partition(state);
+    iproduct!(inside.into_iter(), outside.into_iter()).map(|(out, into)| Move::Swap(out, into))
+}
+
+pub fn double_swaps(state: &[bool]) -> impl Iterator<Item = Move> {
+    let (inside, outside) = partition(state);
+    let outs = inside.into_iter().tuple_combinations().collect::<Vec<_>>();
+    let intos = outside.into_iter().tuple_combinations().collect::<Vec<_>>();
+    iproduct!(outs.into_iter(), intos.into_iter()).map(|(out, into)| Move::DoubleSwap(out, into))
+}
+
+pub fn two_for_one(state: &[bool]) -> impl Iterator<Item = Move> {
+    let (inside, outside) = partition(state);
+    let outs = inside.into_iter().tuple_combinations().collect::<Vec<_>>();
+    iproduct!(outs.into_iter(), outside.into_iter()).map(|(out, into)| Move::TwoForOne(out, into))
+}
+
+pub fn one_for_two(state: &[bool]) -> impl Iterator<Item = Move> {
+    let (inside, outside) = partition(state);
+    let intos = outside.into_iter().tuple_combinations().collect::<Vec<_>>();
+    iproduct!(inside.into_iter(), intos.into_iter()).map(|(out, into)| Move::OneForTwo(out, into))
+}
+
+impl Neighbourhood {
+    pub fn moves(self, state: &[bool]) -> Box<dyn Iterator<Item = Move>> {
+        match self {
+            Neighbourhood::Flip => Box::new(flips(state.len())),
+            Neighbourhood::Swap => Box::new(swaps(state)),
+            Neighbourhood::DoubleSwap => Box::new(double_swaps(state)),
+            Neighbourhood::TwoForOne => Box::new(two_for_one(state)),
+            Neighbourhood::OneForTwo => Box::new(one_for_two(state)),
+        }
+    }
+}
+
+// removes items with worst ratio (items are sorted by ratio), until state fits
+pub fn repair(state: &mut [bool], items: &[Item], max_weight: u32) -> (u32, u32) {
+    let (cost, weight) = cost_weight(state, items);
+    (0..state.len())
+        .rev()
+        .fold((cost, weight), |(cost, weight), i| {
+            if weight > max_weight && state[i] {
+                state[i] = false;
+                (cost - items[i].cost, weight - items[i].weight)
+            } else {
+                (cost, weight)
+            }
+        })
+}
+
+// 2-2 exchanges have O(n^4) moves, so they are searched only on request
+pub fn local_search(
+    state: &mut [bool],
+    items: &[Item],
+    max_weight: u32,
+    double_swap: bool,
+) -> (u32, u32) {
+    let neighbourhoods: &[Neighbourhood] = if double_swap {
+        &[
+            Neighbourhood::Flip,
+            Neighbourhood::Swap,
+            Neighbourhood::DoubleSwap,
+        ]
+    } else {
+        &[Neighbourhood::Flip, Neighbourhood::Swap]
+    };
+    descent(state, items, max_weight, neighbourhoods)
+}
+
+// first improvement descent, later neighbourhoods are searched only when earlier have no
+// improving move, fitting state stays fitting, returns (cost, weight) of local optimum
+pub fn descent(
+    state: &mut [bool],
+    items: &[Item],
+    max_weight: u32,
+    neighbourhoods: &[Neighbourhood],
+) -> (u32, u32) {
+    let (mut cost, mut weight) = cost_weight(state, items);
+    loop {
+        let improving = {
+            let improves = |m: &Move| {
+                let (new_cost, new_weight) = m.evaluate(state, items, cost, weight);
+                new_cost > cost && new_weight <= max_weight
+            };
+            neighbourhoods
+                .iter()
+                .find_map(|n| n.moves(state).find(improves))
+        };
+        match improving {
+            Some(m) => {
+                let (new_cost, new_weight) = m.evaluate(state, items, cost, weight);
+                m.apply(state);
+                cost = new_cost;
+                weight = new_weight;
+            }
+            None => return (cost, weight),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use super::*;
+
+    #[test]
+    fn sizes_of_neighbourhoods() {
+        let state = [true, true, false, false, false];
+        assert_eq!(Neighbourhood::Flip.moves(&state).count(), 5);
+        assert_eq!(Neighbourhood::Swap.moves(&state).count(), 2 * 3);
+        assert_eq!(Neighbourhood::DoubleSwap.moves(&state).count(), 3);
+        assert_eq!(Neighbourhood::TwoForOne.moves(&state).count(), 3);
+        assert_eq!(Neighbourhood::OneForTwo.moves(&state).count(), 2 * 3);
+    }
+
+    #[test]
+    fn evaluate_agrees_with_apply() {
+        let problem = problem(0, 100, &[(3, 4), (5, 7), (2, 1), (8, 9), (4, 4)]);
+        let state = [true, false, true, false, true];
+        let (cost, weight) = cost_weight(&state, &problem.items);
+        let m = Move::DoubleSwap((0, 2), (1, 3));
+        let mut moved = state;
+        m.apply(&mut moved);
+        assert_eq!(moved, [false, true, false, true, true]);
+        assert_eq!(
+            m.evaluate(&state, &problem.items, cost, weight),
+            cost_weight(&moved, &problem.items)
+        );
+    }
+
+    #[test]
+    fn local_search_reaches_local_optimum() {
+        for &double_swap in &[false, true] {
+            for problem in &random_problems(4, 10, 12) {
+                let mut state = vec![false; problem.size];
+                let (cost, weight) =
+                    local_search(&mut state, &problem.items, problem.max_weight, double_swap);
+                assert_eq!((cost, weight), cost_weight(&state, &problem.items));
+                assert!(weight <= problem.max_weight);
+                let improving = flips(problem.size).chain(swaps(&state)).find(|m| {
+                    let (new_cost, new_weight) = m.evaluate(&state, &problem.items, cost, weight);
+                    new_cost > cost && new_weight <= problem.max_weight
+                });
+                assert_eq!(improving, None);
+            }
+        }
+    }
+
+    #[test]
+    fn repair_removes_worst_items() {
+        let problem = problem(0, 6, &[(2, 6), (3, 6), (4, 4)]);
+        let mut state = [true, true, true];
+        assert_eq!(
+            repair(&mut state, &problem.items, problem.max_weight),
+            (12, 5)
+        );
+        assert_eq!(state, [true, true, false]);
+    }
+}
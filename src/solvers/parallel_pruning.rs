@@ -0,0 +1,162 @@
+use super::{
+    bounds::{Bounds, UpperBound},
+    greedy::construction_greedy_inner,
+    utils::{best_valued_item_fit, sort_by_cost_weight_ratio},
+    Item, Problem, Solution, SolverTrait,
+};
+
+use rayon::ThreadPool;
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc, Mutex,
+};
+
+// Branch and bound over the same tree as pruning. Subtrees near the root are solved
+// as rayon tasks (idle threads steal them), deeper ones sequentially. All threads
+// prune against one shared best cost, so solution found by one thread cuts the others.
+#[derive(Debug, Clone)]
+pub struct ParallelPruningSolver {
+    // pool is shared by all problems
+    pub pool: Arc<ThreadPool>,
+    pub bound: UpperBound,
+}
+
+// levels above the number of threads, which are still split into tasks
+const EXTRA_SPLIT_LEVELS: u32 = 4;
+
+struct Incumbent<'a> {
+    items: &'a [Item],
+    bounds: Bounds,
+    best_cost: AtomicU32,
+    best_solution: Mutex<Vec<bool>>,
+}
+
+impl Incumbent<'_> {
+    fn improve(&self, cost: u32, state: &[bool]) {
+        // best cost is changed only under lock, so it always belongs to best solution
+        let mut best_solution = self.best_solution.lock().unwrap();
+        if cost > self.best_cost.load(Ordering::Relaxed) {
+            self.best_cost.store(cost, Ordering::Relaxed);
+            best_solution.copy_from_slice(state);
+        }
+    }
+
+    fn search(&self, state: &mut Vec<bool>, index: usize, cost: u32, weight: u32, splits: u32) {
+        if self.bounds.upper(index, cost, weight) <= self.best_cost.load(Ordering::Relaxed) {
+            return;
+        }
+        if index == self.items.len() {
+            self.improve(cost, state);
+            return;
+        }
+        let item = self.items[index];
+        let fits = weight + item.weight <= self.bounds.max_weight();
+        if splits > 0 {
+            let mut with_state = state.clone();
+            with_state[index] = true;
+            rayon::join(
+                || {
+                    if fits {
+                        self.search(
+                            &mut with_state,
+                            index + 1,
+                            cost + item.cost,
+                            weight + item.weight,
+                            splits - 1,
+                        )
+                    }
+                },
+                || self.search(state, index + 1, cost, weight, splits - 1),
+            );
+        } else {
+            if fits {
+                state[index] = true;
+                self.search(state, index + 1, cost + item.cost, weight + item.weight, 0);
+                state[index] = false;
+            }
+            self.search(state, index + 1, cost, weight, 0);
+        }
+    }
+}
+
+impl SolverTrait for ParallelPruningSolver {
+    fn construction(&self, problem: &Problem) -> Solution {
+        let (items, mapping) = sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
+
+        if items.is_empty() {
+            return Solution::empty(problem.id, problem.size);
+        }
+
+        // redux solution is initial incumbent
+        let identity = (0..items.len()).collect::<Vec<_>>();
+        let (greedy, greedy_cost) =
+            construction_greedy_inner(&items, &identity, items.len(), problem.max_weight);
+        let (item_cost, index) = best_valued_item_fit(&items, problem.max_weight);
+        let (initial, initial_cost) = if item_cost > greedy_cost {
+            ((0..items.len()).map(|i| i == index).collect(), item_cost)
+        } else {
+            (greedy, greedy_cost)
+        };
+
+        let incumbent = Incumbent {
+            items: &items,
+            bounds: Bounds::new(&items, problem.max_weight, self.bound),
+            best_cost: AtomicU32::new(initial_cost),
+            best_solution: Mutex::new(initial),
+        };
+
+        let splits =
+            usize::BITS - self.pool.current_num_threads().leading_zeros() + EXTRA_SPLIT_LEVELS;
+        self.pool
+            .install(|| incumbent.search(&mut vec![false; items.len()], 0, 0, 0, splits));
+
+        let best_cost = incumbent.best_cost.into_inner();
+        let best_solution = incumbent.best_solution.into_inner().unwrap();
+
+        Solution {
+            id: problem.id,
+            size: problem.size,
+            cost: best_cost,
+            items: Some(best_solution.into_iter().enumerate().fold(
+                vec![false; problem.size],
+                |mut acc, (i, x)| {
+                    acc[mapping[i]] = x;
+                    acc
+                },
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use super::*;
+
+    #[test]
+    fn agrees_with_dynamic_weight() {
+        for &threads in &[1, 2, 4] {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            assert_exact(&ParallelPruningSolver {
+                pool: Arc::new(pool),
+                bound: UpperBound::Dantzig,
+            });
+        }
+    }
+
+    #[test]
+    fn agrees_with_dynamic_weight_on_bigger_problems() {
+        let solver = ParallelPruningSolver {
+            pool: Arc::new(rayon::ThreadPoolBuilder::new().build().unwrap()),
+            bound: UpperBound::MartelloToth,
+        };
+        for problem in &random_problems(13, 5, 30) {
+            let solution = solver.construction(problem);
+            check_solution(problem, &solution);
+            assert_eq!(solution.cost, optimum(problem));
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use super::{
+    neighbourhood::{cost_weight, descent, Neighbourhood},
+    Problem, Solution,
+};
+
+// Improves any fitting solution to local optimum of filling free capacity, swaps
+// and 2-for-1 (both directions) exchanges. Missing or overweight solutions are returned as they are.
+pub fn polish(problem: &Problem, solution: Solution) -> Solution {
+    let mut state = match solution.items {
+        Some(ref items) => items.clone(),
+        None => return solution,
+    };
+    if cost_weight(&state, &problem.items).1 > problem.max_weight {
+        return solution;
+    }
+    let (cost, _) = descent(
+        &mut state,
+        &problem.items,
+        problem.max_weight,
+        &[
+            Neighbourhood::Flip,
+            Neighbourhood::Swap,
+            Neighbourhood::TwoForOne,
+            Neighbourhood::OneForTwo,
+        ],
+    );
+    Solution {
+        cost,
+        items: Some(state),
+        ..solution
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{greedy::GreedySolver, test_utils::*, SolverTrait};
+    use super::*;
+
+    #[test]
+    fn improves_greedy_solutions() {
+        for problem in edge_problems().iter().chain(&random_problems(9, 20, 15)) {
+            let greedy = GreedySolver().construction(problem);
+            let polished = polish(problem, greedy.clone());
+            check_solution(problem, &polished);
+            assert!(polished.cost >= greedy.cost);
+            assert!(polished.cost <= optimum(problem));
+        }
+    }
+
+    #[test]
+    fn exchanges_one_item_for_two() {
+        let problem = problem(0, 10, &[(10, 12), (5, 7), (5, 7)]);
+        let solution = Solution {
+            id: 0,
+            size: 3,
+            cost: 12,
+            items: Some(vec![true, false, false]),
+        };
+        assert_eq!(polish(&problem, solution).cost, 14);
+    }
+
+    #[test]
+    fn missing_and_overweight_solutions_are_kept() {
+        let problem = problem(0, 5, &[(4, 4), (3, 3)]);
+        let none = Solution::none(0, 2);
+        assert_eq!(polish(&problem, none.clone()), none);
+        let overweight = Solution {
+            id: 0,
+            size: 2,
+            cost: 7,
+            items: Some(vec![true, true]),
+        };
+        assert_eq!(polish(&problem, overweight.clone()), overweight);
+    }
+}
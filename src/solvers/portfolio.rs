@@ -0,0 +1,148 @@
+use super::{Methods, Problem, Solution, Solver, SolverTrait};
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc, Arc, Mutex,
+};
+use std::thread;
+
+// Runs exact solvers concurrently, each in own thread. First solution is returned,
+// because it's optimal, and others are cancelled. Only cancellable solvers are allowed,
+// so joining of their threads doesn't delay result and no thread outlives construction.
+#[derive(Debug, Clone)]
+pub struct PortfolioSolver {
+    pub solvers: Vec<(Methods, Solver)>,
+    // method, which solved last problem
+    pub winner: Arc<Mutex<Option<Methods>>>,
+}
+
+impl PortfolioSolver {
+    pub fn last_winner(&self) -> Option<Methods> {
+        *self.winner.lock().unwrap()
+    }
+}
+
+impl SolverTrait for PortfolioSolver {
+    // all solvers run at once
+    fn memory_estimate(&self, problem: &Problem) -> usize {
+        self.solvers
+            .iter()
+            .map(|(_, solver)| solver.memory_estimate(problem))
+            .fold(0, usize::saturating_add)
+    }
+
+    fn construction(&self, problem: &Problem) -> Solution {
+        let cancel = AtomicBool::new(false);
+        let (sender, receiver) = mpsc::channel();
+        let first = thread::scope(|scope| {
+            for (method, solver) in &self.solvers {
+                let (sender, cancel) = (sender.clone(), &cancel);
+                scope.spawn(move || {
+                    if let Some(solution) = solver.cancellable_construction(problem, cancel) {
+                        // only first solution is received, later ones are dropped
+                        let _ = sender.send((*method, solution));
+                    }
+                });
+            }
+            drop(sender);
+            // fails only when no solver returned solution
+            let first = receiver.recv().ok();
+            cancel.store(true, Ordering::Relaxed);
+            first
+        });
+        let (winner, solution) = match first {
+            Some((winner, solution)) => (Some(winner), solution),
+            None => (None, Solution::none(problem.id, problem.size)),
+        };
+        *self.winner.lock().unwrap() = winner;
+        solution
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        test_utils::*, DynamicCostSolver, DynamicWeightSolver, Opts, PruningSolver, UpperBound,
+    };
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::time::{Duration, Instant};
+    use structopt::StructOpt;
+
+    fn portfolio(solvers: Vec<(Methods, Solver)>) -> PortfolioSolver {
+        PortfolioSolver {
+            solvers,
+            winner: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    #[test]
+    fn agrees_with_dynamic_weight() {
+        assert_exact(&portfolio(vec![
+            (
+                Methods::Pruning,
+                Solver::Pruning(PruningSolver {
+                    bound: UpperBound::Dantzig,
+                }),
+            ),
+            (
+                Methods::DynamicWeight,
+                Solver::DynamicWeight(DynamicWeightSolver()),
+            ),
+        ]));
+    }
+
+    #[test]
+    fn slow_solver_is_cancelled_and_joined() {
+        // even subset sum with odd capacity, upper bound is never reached, so pruning
+        // searches exponential tree, but dynamic-cost takes milliseconds
+        let mut rng = StdRng::seed_from_u64(16);
+        let items = (0..60)
+            .map(|_| {
+                let weight = 2 * rng.gen_range(1, 500);
+                (weight, weight)
+            })
+            .collect::<Vec<_>>();
+        let total_weight = items.iter().map(|x| x.0).sum::<u32>();
+        let problem = problem(0, total_weight / 2 + 1 - total_weight / 2 % 2, &items);
+        let portfolio = portfolio(vec![
+            (
+                Methods::Pruning,
+                Solver::Pruning(PruningSolver {
+                    bound: UpperBound::Dantzig,
+                }),
+            ),
+            (
+                Methods::DynamicCost,
+                Solver::DynamicCost(DynamicCostSolver()),
+            ),
+        ]);
+        let start = Instant::now();
+        let solution = portfolio.construction(&problem);
+        // pruning thread is joined, so it had to stop on cancel
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(portfolio.last_winner(), Some(Methods::DynamicCost));
+        assert_eq!(solution.cost, optimum(&problem));
+    }
+
+    #[test]
+    fn only_exact_and_cancellable_methods() {
+        let opts = |portfolio: &str| {
+            Opts::from_iter_safe(&["knapsack", &format!("--portfolio={}", portfolio)]).unwrap()
+        };
+        let from = |portfolio: &str| Solver::from_method(Methods::Portfolio, &opts(portfolio));
+        assert!(from("pruning,dynamic-weight,dynamic-cost,dynamic-sparse").is_ok());
+        for method in &[
+            "naive",
+            "parallel-pruning",
+            "dynamic-weight-linear",
+            "dynamic-cost-linear",
+            "dynamic-weight-parallel",
+            "dynamic-weight-simd",
+            "greedy",
+            "portfolio",
+        ] {
+            assert!(from(&format!("pruning,{}", method)).is_err(), "{}", method);
+        }
+    }
+}
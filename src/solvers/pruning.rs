@@ -1,23 +1,34 @@
 use super::{
+    bounds::{Bounds, UpperBound},
     utils::{
-        best_valued_item_fit, calc_remaining_cost, calc_remaining_weight, max_cost_from_rem,
-        sort_by_cost_weight_ratio,
+        best_valued_item_fit, calc_remaining_cost, calc_remaining_weight, sort_by_cost_weight_ratio,
     },
     Problem, Solution, SolverTrait,
 };
+use std::sync::atomic::{AtomicBool, Ordering};
 
 #[derive(Debug, Clone)]
-pub struct PruningSolver();
+pub struct PruningSolver {
+    pub bound: UpperBound,
+}
 
 #[derive(Debug, Clone)]
-struct ProblemWithAddedInfo {
+struct ProblemWithAddedInfo<'a> {
     p: Problem,
     rem_weight: Vec<u32>,
     rem_cost: Vec<u32>,
+    bounds: Bounds,
     best_solution: Vec<bool>,
+    cancel: &'a AtomicBool,
 }
 
-pub fn pruning(problem: &Problem, inject_solution: Solution) -> Solution {
+// None when search is cancelled
+pub fn pruning(
+    problem: &Problem,
+    inject_solution: Solution,
+    bound: UpperBound,
+    cancel: &AtomicBool,
+) -> Option<Solution> {
     fn rec_fn(
         problem: &mut ProblemWithAddedInfo,
         cost: u32,
@@ -26,15 +37,17 @@ pub fn pruning(problem: &Problem, inject_solution: Solution) -> Solution {
         best_cost: u32,
         last_selected: bool,
     ) -> u32 {
+        if problem.cancel.load(Ordering::Relaxed) {
+            return best_cost;
+        }
         if index < problem.p.items.len() {
             let ratio = problem.p.items[index].cost_weight_ratio();
-            if (problem.p.max_weight - weight).min(problem.rem_weight[index])
-                * ratio.numer() / ratio.denom()
+            if (problem.p.max_weight - weight).min(problem.rem_weight[index]) * ratio.numer()
+                / ratio.denom()
                 + cost
                 < best_cost
                 || cost + problem.rem_cost[index] <= best_cost
-                // max_cost_from_rem is O(log n)
-                || best_cost >= cost + max_cost_from_rem(&problem.rem_cost[index..], &problem.rem_weight[index..], problem.p.max_weight - weight)
+                || best_cost >= problem.bounds.upper(index, cost, weight)
             {
                 return best_cost;
             }
@@ -93,13 +106,19 @@ pub fn pruning(problem: &Problem, inject_solution: Solution) -> Solution {
     let mut aug_problem = ProblemWithAddedInfo {
         rem_cost: calc_remaining_cost(&items),
         rem_weight: calc_remaining_weight(&items),
+        bounds: Bounds::new(&items, problem.max_weight, bound),
         best_solution: inject_items,
+        cancel,
         p: Problem { items, ..*problem },
     };
 
     let cost = rec_fn(&mut aug_problem, 0, 0, 0, inject_cost, true);
 
-    Solution {
+    if cancel.load(Ordering::Relaxed) {
+        return None;
+    }
+
+    Some(Solution {
         id: problem.id,
         size: problem.size,
         cost,
@@ -112,11 +131,16 @@ pub fn pruning(problem: &Problem, inject_solution: Solution) -> Solution {
                 acc
             },
         )),
-    }
+    })
 }
 
 impl SolverTrait for PruningSolver {
     fn construction(&self, problem: &Problem) -> Solution {
+        self.cancellable_construction(problem, &AtomicBool::new(false))
+            .unwrap()
+    }
+
+    fn cancellable_construction(&self, problem: &Problem, cancel: &AtomicBool) -> Option<Solution> {
         let best_item = best_valued_item_fit(&problem.items, problem.max_weight);
         pruning(
             problem,
@@ -126,6 +150,8 @@ impl SolverTrait for PruningSolver {
                 cost: best_item.0,
                 items: Some((0..problem.items.len()).map(|i| i == best_item.1).collect()),
             },
+            self.bound,
+            cancel,
         )
     }
 }
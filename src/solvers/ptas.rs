@@ -0,0 +1,103 @@
+use super::{
+    greedy::construction_greedy_inner, utils::sort_by_cost_weight_ratio, Item, Problem, Solution,
+    SolverTrait,
+};
+use itertools::Itertools;
+
+// Sahni's scheme: guesses up to k items of optimal solution and completes them greedily,
+// result is at least (1 - 1 / (k + 1)) * optimum in O(n^(k + 1)) time and O(n) memory
+#[derive(Debug, Clone)]
+pub struct PTASSolver {
+    pub k: usize,
+}
+
+impl PTASSolver {
+    // smallest k, which guarantees (1 - epsilon) * optimum
+    pub fn from_epsilon(epsilon: f64) -> PTASSolver {
+        PTASSolver {
+            k: (1.0 / epsilon).ceil() as usize - 1,
+        }
+    }
+}
+
+impl SolverTrait for PTASSolver {
+    fn construction(&self, problem: &Problem) -> Solution {
+        let (items, mappings) = sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
+
+        let greedy = construction_greedy_inner(&items, &mappings, problem.size, problem.max_weight);
+
+        let (best_solution, best_cost) = (1..=self.k.min(items.len()))
+            .flat_map(|k| (0..items.len()).combinations(k))
+            .filter_map(|guess| {
+                let weight: u64 = guess.iter().map(|&i| items[i].weight as u64).sum();
+                if weight > problem.max_weight as u64 {
+                    return None;
+                }
+                let (rest, rest_mappings): (Vec<Item>, Vec<usize>) = items
+                    .iter()
+                    .zip(mappings.iter())
+                    .enumerate()
+                    .filter(|(i, _)| !guess.contains(i))
+                    .map(|(_, (item, mapping))| (*item, *mapping))
+                    .unzip();
+                let (mut solution, cost) = construction_greedy_inner(
+                    &rest,
+                    &rest_mappings,
+                    problem.size,
+                    problem.max_weight - weight as u32,
+                );
+                guess.iter().for_each(|&i| solution[mappings[i]] = true);
+                Some((
+                    solution,
+                    cost + guess.iter().map(|&i| items[i].cost).sum::<u32>(),
+                ))
+            })
+            .fold(greedy, |best, candidate| {
+                if candidate.1 > best.1 {
+                    candidate
+                } else {
+                    best
+                }
+            });
+
+        Solution {
+            id: problem.id,
+            size: problem.size,
+            cost: best_cost,
+            items: Some(best_solution),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use super::*;
+
+    #[test]
+    fn k_from_epsilon() {
+        assert_eq!(PTASSolver::from_epsilon(0.5).k, 1);
+        assert_eq!(PTASSolver::from_epsilon(0.3).k, 3);
+        assert_eq!(PTASSolver::from_epsilon(0.1).k, 9);
+    }
+
+    #[test]
+    fn guarantees_ratio_of_optimum() {
+        for k in 0..=3 {
+            assert_feasible(&PTASSolver { k })
+                .iter()
+                .for_each(|&(cost, optimum)| {
+                    assert!(cost as f64 >= optimum as f64 * k as f64 / (k + 1) as f64)
+                });
+        }
+    }
+
+    #[test]
+    fn all_guessed_items_is_exact() {
+        let problems = random_problems(2, 5, 6);
+        for problem in &problems {
+            let solution = PTASSolver { k: 6 }.construction(problem);
+            assert_eq!(solution.cost, optimum(problem));
+        }
+    }
+}
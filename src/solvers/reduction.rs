@@ -0,0 +1,140 @@
+use super::{
+    bounds::dantzig,
+    greedy::construction_greedy_inner,
+    utils::{best_valued_item_fit, sort_by_cost_weight_ratio},
+    Problem, Solution,
+};
+
+// Problem without items, which are known to be (or not to be) in some optimal solution.
+// Item is fixed to 0, when LP bound with it can't reach redux solution, and fixed
+// to 1, when LP bound without it can't. Items heavier than capacity are fixed to 0.
+#[derive(Debug, Clone)]
+pub struct Reduction {
+    pub problem: Problem,
+    // value of every original item, None for items left in reduced problem
+    fixed: Vec<Option<bool>>,
+    fixed_cost: u32,
+}
+
+pub fn reduce(problem: &Problem) -> Reduction {
+    let (items, mappings) = sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
+    let max_weight = problem.max_weight;
+
+    let identity = (0..items.len()).collect::<Vec<_>>();
+    let lower_bound = construction_greedy_inner(&items, &identity, items.len(), max_weight)
+        .1
+        .max(best_valued_item_fit(&items, max_weight).0);
+
+    // heavy items are already filtered out by sorting
+    let mut fixed = vec![Some(false); problem.size];
+    for (j, item) in items.iter().enumerate() {
+        let others = || {
+            items
+                .iter()
+                .enumerate()
+                .filter(move |&(i, _)| i != j)
+                .map(|(_, item)| item)
+        };
+        // strict comparisons keep at least one solution with cost of lower bound
+        fixed[mappings[j]] =
+            if item.cost + dantzig(others(), max_weight - item.weight) < lower_bound {
+                Some(false)
+            } else if dantzig(others(), max_weight) < lower_bound {
+                Some(true)
+            } else {
+                None
+            };
+    }
+
+    // every solution reaching lower bound contains all items fixed to 1, so they fit together
+    let (fixed_cost, fixed_weight) = fixed
+        .iter()
+        .zip(problem.items.iter())
+        .filter(|(&x, _)| x == Some(true))
+        .fold((0, 0), |(cost, weight), (_, item)| {
+            (cost + item.cost, weight + item.weight)
+        });
+
+    let free_items = fixed
+        .iter()
+        .zip(problem.items.iter())
+        .filter(|(x, _)| x.is_none())
+        .map(|(_, item)| *item)
+        .collect::<Vec<_>>();
+
+    Reduction {
+        problem: Problem {
+            id: problem.id,
+            max_weight: max_weight - fixed_weight,
+            size: free_items.len(),
+            min_cost: problem.min_cost.map(|c| c.saturating_sub(fixed_cost)),
+            items: free_items,
+        },
+        fixed,
+        fixed_cost,
+    }
+}
+
+impl Reduction {
+    pub fn fixed_count(&self) -> usize {
+        self.fixed.len() - self.problem.size
+    }
+
+    // solution of reduced problem to solution of original one
+    pub fn restore(&self, solution: Solution) -> Solution {
+        let items = match solution.items {
+            Some(items) => items,
+            None => return Solution::none(solution.id, self.fixed.len()),
+        };
+        let mut free = items.into_iter();
+        Solution {
+            id: solution.id,
+            size: self.fixed.len(),
+            cost: solution.cost + self.fixed_cost,
+            items: Some(
+                self.fixed
+                    .iter()
+                    .map(|x| x.unwrap_or_else(|| free.next().unwrap()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_utils::*, DynamicWeightSolver, SolverTrait};
+    use super::*;
+
+    #[test]
+    fn reduced_problem_keeps_optimum() {
+        for problem in edge_problems().iter().chain(&random_problems(12, 30, 15)) {
+            let reduction = reduce(problem);
+            assert_eq!(
+                reduction.fixed_count() + reduction.problem.size,
+                problem.size
+            );
+            let solution =
+                reduction.restore(DynamicWeightSolver().construction(&reduction.problem));
+            check_solution(problem, &solution);
+            assert_eq!(solution.cost, optimum(problem), "problem {}", problem.id);
+        }
+    }
+
+    #[test]
+    fn fixes_dominant_and_heavy_items() {
+        // first item is in every good solution, last one doesn't fit
+        let problem = problem(0, 10, &[(5, 50), (3, 4), (4, 5), (2, 2), (11, 100)]);
+        let reduction = reduce(&problem);
+        assert_eq!(reduction.fixed[0], Some(true));
+        assert_eq!(reduction.fixed[4], Some(false));
+        assert_eq!(reduction.problem.max_weight, 5);
+    }
+
+    #[test]
+    fn missing_solution_stays_missing() {
+        let problem = &random_problems(1, 1, 10)[0];
+        let restored = reduce(problem).restore(Solution::none(problem.id, 0));
+        assert_eq!(restored, Solution::none(problem.id, problem.size));
+    }
+}
@@ -1,163 +1,246 @@
-use super::{sort_by_cost_weight_ratio, Item, Problem, Solution, SolverTrait, ratio};
+use super::{
+    greedy::construction_greedy_inner,
+    neighbourhood::{cost_weight, flips, repair, swaps},
+    sort_by_cost_weight_ratio,
+    utils::{best_valued_item_fit, seeded_rng},
+    DisplayError, Item, Problem, Solution, SolverTrait,
+};
 
-use arrayvec::ArrayVec;
-use itertools::izip;
+use rand::Rng;
+use std::str::FromStr;
 
 #[derive(Debug, Clone)]
 pub struct TabuSearchSolver {
+    // number of iterations, for which flipped items are tabu
     pub memory_size: usize,
     pub iterations: usize,
+    pub initial: InitialState,
+    pub restart: Restart,
+    // restart after so many iterations without improvement of best solution
+    pub restart_after: Option<usize>,
+    pub seed: u64,
 }
 
-fn cost_weight(state: &[bool], items: &[Item]) -> (u32, u32) {
-    state
-        .iter()
-        .zip(items.iter())
-        .filter(|(&in_pack, _)| in_pack)
-        .fold((0, 0), |(cost, weight), (_, item)| {
-            (cost + item.cost, weight + item.weight)
-        })
+#[derive(Debug, Clone, Copy)]
+pub enum InitialState {
+    Full,
+    Empty,
+    Greedy,
+    Redux,
 }
 
-struct TabuMemory {
-    tabu_raw: Vec<bool>,
-    capacity: usize,
-    size: usize,
-    target: usize,
-    problem_size: usize,
+#[derive(Debug, Clone, Copy)]
+pub enum Restart {
+    // random state repaired to fit
+    Random,
+    // greedily packs items, which were in knapsack least often
+    Frequency,
 }
 
+impl FromStr for InitialState {
+    type Err = DisplayError;
+    fn from_str(name: &str) -> Result<InitialState, DisplayError> {
+        match name {
+            "full" => Ok(InitialState::Full),
+            "empty" => Ok(InitialState::Empty),
+            "greedy" => Ok(InitialState::Greedy),
+            "redux" => Ok(InitialState::Redux),
+            _ => Err(format!(
+                "Initial state {:?} not found, following are valid: full, empty, greedy, redux.",
+                name
+            )
+            .into()),
+        }
+    }
+}
 
-impl<'a> TabuMemory {
-    fn new(problem_size: usize, memory_size: usize) -> TabuMemory {
-        let tabu_raw = vec![false; problem_size * memory_size];
-        TabuMemory {
-            capacity: memory_size,
-            tabu_raw,
-            size: 0,
-            problem_size,
-            target: 0,
+impl FromStr for Restart {
+    type Err = DisplayError;
+    fn from_str(name: &str) -> Result<Restart, DisplayError> {
+        match name {
+            "random" => Ok(Restart::Random),
+            "frequency" => Ok(Restart::Frequency),
+            _ => Err(format!(
+                "Restart {:?} not found, following are valid: random, frequency.",
+                name
+            )
+            .into()),
         }
     }
+}
 
-    fn blacklist<'b>(&self, state: &[bool], pass_memory: &'b mut [bool]) -> &'b [bool] {
-        pass_memory
-            .iter_mut()
-            .for_each(|x| *x = false);
-        self
-            .tabu_raw
-            .chunks(self.problem_size)
-            .take(self.size)
-            .fold(pass_memory, |blacklist, memory_state| {
-                // finds at most 2 different bools beetwen state and items in memory
-                // on 1 sets blacklist on 2 nothing
-                match memory_state
-                    .iter()
-                    .zip(state.iter())
-                    .enumerate()
-                    .filter(|(_, (m, s))| m != s)
-                    .collect::<ArrayVec<[_; 2]>>()
-                    .as_slice()
-                {
-                    &[(i, _)] => blacklist[i] = true,
-                    _ => (),
-                };
-                blacklist
-            }) as &[bool]
+impl InitialState {
+    fn state(self, items: &[Item], max_weight: u32) -> Vec<bool> {
+        match self {
+            InitialState::Full => vec![true; items.len()],
+            InitialState::Empty => vec![false; items.len()],
+            InitialState::Greedy | InitialState::Redux => {
+                let identity = (0..items.len()).collect::<Vec<_>>();
+                let (greedy, cost) =
+                    construction_greedy_inner(items, &identity, items.len(), max_weight);
+                let (item_cost, index) = best_valued_item_fit(items, max_weight);
+                if let (InitialState::Redux, true) = (self, item_cost > cost) {
+                    (0..items.len()).map(|i| i == index).collect()
+                } else {
+                    greedy
+                }
+            }
+        }
     }
+}
 
-    fn insert(&mut self, state: &[bool]) {
-        self.tabu_raw
-            .chunks_mut(self.problem_size)
-            .skip(self.target)
-            .take(1)
-            .for_each(|x| {
-                x.iter_mut()
-                    .zip(state.iter())
-                    .for_each(|(mem, &state)| *mem = state)
-            });
-        self.size = (self.size + 1).min(self.capacity);
-        self.target = (self.target + 1) % self.capacity;
+// fitting states are always better, then by cost, overweight ones by lesser overweight
+fn score(cost: u32, weight: u32, max_weight: u32) -> (bool, i64) {
+    if weight <= max_weight {
+        (true, cost as i64)
+    } else {
+        (false, max_weight as i64 - weight as i64)
     }
 }
 
 impl SolverTrait for TabuSearchSolver {
     fn construction(&self, problem: &Problem) -> Solution {
-
         // Maybe this mappings helps little? not sure
         let (items, mapping) = sort_by_cost_weight_ratio(&problem.items, problem.max_weight);
 
-        if items.len() == 0 {
+        if items.is_empty() {
             return Solution::empty(problem.id, problem.size);
         }
 
-        let mut state = vec![true; items.len()];
-        let mut best_solution = vec![false; items.len()];
-        let mut best_cost = 0;
-
-        let mut tabu = TabuMemory::new(items.len(), self.memory_size);
-
-        let mut blacklist_for_less_allocations = vec![false; items.len()];
+        let max_weight = problem.max_weight;
 
-        const RANDOM_CONST: u8 = 5;
+        let mut rng = seeded_rng(self.seed, problem);
 
-        //state.iter_mut().for_each(|s| *s = rand::random::<u8>() > RANDOM_CONST);
+        let mut state = self.initial.state(&items, max_weight);
+        let (mut cost, mut weight) = cost_weight(&state, &items);
 
-        for _ in 0..self.iterations {
-            let (cost, weight) = cost_weight(&state, &items);
+        let mut best_solution = vec![false; items.len()];
+        let mut best_cost = 0;
+        if weight <= max_weight {
+            best_solution.copy_from_slice(&state);
+            best_cost = cost;
+        }
 
-            let blacklist = tabu.blacklist(&state, &mut blacklist_for_less_allocations);
-            // maximize function (- over_capacity, cost, _)
-            let max_cost_fn = izip!((0..), state.iter(), items.iter(), blacklist.iter())
-                .filter(|(_, _, _, &blacklist)| !blacklist)
-                .map(|(i, &current_state, item, _)| {
-                    let (new_weight, new_cost) = if current_state {
-                        (weight - item.weight, cost - item.cost)
-                    } else {
-                        (weight + item.weight, cost + item.cost)
-                    };
-                    if new_weight > problem.max_weight {
-                        (ratio::new(new_cost, new_weight), new_cost, new_weight, i)
-                    } else {
-                        (ratio::new(3*new_cost, 2*new_weight.max(1)), new_cost, new_weight, i)
-                    }
+        // attribute memory: item is tabu until given iteration
+        let mut tabu_until = vec![0; items.len()];
+        // long term memory: number of iterations, when item was in knapsack
+        let mut frequency = vec![0usize; items.len()];
+        let mut since_improvement = 0;
+
+        for iteration in 0..self.iterations {
+            let best_move = flips(items.len())
+                .chain(swaps(&state))
+                .map(|m| {
+                    let (new_cost, new_weight) = m.evaluate(&state, &items, cost, weight);
+                    (
+                        score(new_cost, new_weight, max_weight),
+                        new_cost,
+                        new_weight,
+                        m,
+                    )
                 })
-                .max().unwrap_or_else(||{
-                    // reset
-                    let random = rand::random::<usize>() % items.len();
-                    state.iter_mut().for_each(|s| *s = rand::random::<u8>() > RANDOM_CONST);
-                    state[random] = true;
-                    let (cost, weight) = cost_weight(&state, &items);
-                    state[random] = false;
-                    (ratio::new(0,1), cost, weight, random)
-                });
-            tabu.insert(&state);
-            let index_to_switch = max_cost_fn.3;
-            state[index_to_switch] = !state[index_to_switch];
+                // aspiration: tabu move is allowed, when it leads to new best solution
+                .filter(|&(_, new_cost, new_weight, m)| {
+                    m.indices().iter().all(|&i| tabu_until[i] <= iteration)
+                        || (new_weight <= max_weight && new_cost > best_cost)
+                })
+                .max();
+
+            let stagnated = self
+                .restart_after
+                .is_some_and(|after| since_improvement >= after);
+
+            match best_move {
+                Some((_, new_cost, new_weight, m)) if !stagnated => {
+                    m.apply(&mut state);
+                    m.indices()
+                        .iter()
+                        .for_each(|&i| tabu_until[i] = iteration + 1 + self.memory_size);
+                    cost = new_cost;
+                    weight = new_weight;
+                }
+                // all moves are tabu or search stagnated
+                _ => {
+                    match self.restart {
+                        Restart::Random => state.iter_mut().for_each(|s| *s = rng.gen()),
+                        Restart::Frequency => {
+                            let mut order = (0..items.len()).collect::<Vec<_>>();
+                            // stable sort keeps ratio order between same frequencies
+                            order.sort_by_key(|&i| frequency[i]);
+                            order.into_iter().fold(0, |used_weight: u32, i| {
+                                state[i] = used_weight + items[i].weight <= max_weight;
+                                used_weight + if state[i] { items[i].weight } else { 0 }
+                            });
+                        }
+                    }
+                    let repaired = repair(&mut state, &items, max_weight);
+                    cost = repaired.0;
+                    weight = repaired.1;
+                    tabu_until.iter_mut().for_each(|t| *t = 0);
+                    since_improvement = 0;
+                }
+            }
 
-            if max_cost_fn.2 <= problem.max_weight && best_cost < max_cost_fn.1 {
-                best_solution.iter_mut().zip(state.iter()).for_each(|(b, &s)| *b = s);
-                best_cost = max_cost_fn.1;
+            state
+                .iter()
+                .zip(frequency.iter_mut())
+                .filter(|(&used, _)| used)
+                .for_each(|(_, f)| *f += 1);
+
+            since_improvement += 1;
+            if weight <= max_weight && cost > best_cost {
+                best_solution.copy_from_slice(&state);
+                best_cost = cost;
+                since_improvement = 0;
             }
-            //switch state
         }
-        if problem.max_weight < items.iter().zip(best_solution.iter()).map(|(item, &included)| if included {item.weight} else {0}).sum(){
-            Solution::none(problem.id, problem.size)
-        } else {
-            Solution {
-                id: problem.id,
-                cost: best_cost,
-                items: Some(best_solution.into_iter().enumerate().fold(
-                    vec![false; problem.size],
-                    |mut acc, (i, x)| {
-                        if let Some(&mapping) = mapping.get(i) {
-                            acc[mapping] = x;
-                        }
-                        acc
-                    },
-                )),
-                size: problem.size,
+
+        Solution {
+            id: problem.id,
+            cost: best_cost,
+            items: Some(best_solution.into_iter().enumerate().fold(
+                vec![false; problem.size],
+                |mut acc, (i, x)| {
+                    acc[mapping[i]] = x;
+                    acc
+                },
+            )),
+            size: problem.size,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use super::*;
+
+    #[test]
+    fn every_initial_state_and_restart_fits() {
+        for &initial in &[
+            InitialState::Full,
+            InitialState::Empty,
+            InitialState::Greedy,
+            InitialState::Redux,
+        ] {
+            for &restart in &[Restart::Random, Restart::Frequency] {
+                assert_feasible(&TabuSearchSolver {
+                    memory_size: 3,
+                    iterations: 100,
+                    initial,
+                    restart,
+                    restart_after: Some(10),
+                    seed: 42,
+                });
             }
         }
     }
+
+    #[test]
+    fn parse_options() {
+        assert!(matches!("redux".parse(), Ok(InitialState::Redux)));
+        assert!(matches!("frequency".parse(), Ok(Restart::Frequency)));
+        assert!("half".parse::<InitialState>().is_err());
+        assert!("never".parse::<Restart>().is_err());
+    }
 }
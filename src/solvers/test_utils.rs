@@ -0,0 +1,117 @@
+use super::{dynamic_weight::DynamicWeightSolver, Item, Problem, Solution, SolverTrait};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+pub fn problem(id: u32, max_weight: u32, items: &[(u32, u32)]) -> Problem {
+    Problem {
+        id,
+        max_weight,
+        size: items.len(),
+        min_cost: None,
+        items: items
+            .iter()
+            .map(|&(weight, cost)| Item { weight, cost })
+            .collect(),
+    }
+}
+
+// random problems with capacity about half of total weight
+pub fn random_problems(seed: u64, count: usize, size: usize) -> Vec<Problem> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..count as u32)
+        .map(|id| {
+            let items = (0..size)
+                .map(|_| (rng.gen_range(1, 100), rng.gen_range(1, 250)))
+                .collect::<Vec<_>>();
+            let total_weight = items.iter().map(|x| x.0).sum::<u32>();
+            problem(id, total_weight / 2, &items)
+        })
+        .collect()
+}
+
+// problems with capacities over 10^5, so tables span many blocks and chunks
+pub fn heavy_problems(seed: u64, count: usize, size: usize) -> Vec<Problem> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..count as u32)
+        .map(|id| {
+            let items = (0..size)
+                .map(|_| (rng.gen_range(1, 20_000), rng.gen_range(1, 1000)))
+                .collect::<Vec<_>>();
+            let total_weight = items.iter().map(|x| x.0).sum::<u32>();
+            problem(id, total_weight / 2, &items)
+        })
+        .collect()
+}
+
+// no items, nothing fits, everything fits, exact fit and equal items
+pub fn edge_problems() -> Vec<Problem> {
+    vec![
+        problem(0, 10, &[]),
+        problem(1, 5, &[(6, 10), (7, 3), (100, 1)]),
+        problem(2, 100, &[(6, 10), (7, 3), (20, 1)]),
+        problem(3, 10, &[(4, 4), (6, 6), (5, 9), (5, 1)]),
+        problem(4, 12, &[(4, 5), (4, 5), (4, 5), (4, 5), (4, 5)]),
+        problem(5, 0, &[(1, 10), (2, 3)]),
+    ]
+}
+
+// checks that solution fits and its cost is sum of chosen items, returns its weight
+pub fn check_solution(problem: &Problem, solution: &Solution) -> u32 {
+    assert_eq!(solution.id, problem.id);
+    assert_eq!(solution.size, problem.size);
+    let items = solution.items.as_ref().expect("solution without items");
+    assert_eq!(items.len(), problem.size);
+    let (cost, weight) = items
+        .iter()
+        .zip(problem.items.iter())
+        .filter(|(&in_pack, _)| in_pack)
+        .fold((0, 0), |(cost, weight), (_, item)| {
+            (cost + item.cost, weight + item.weight)
+        });
+    assert_eq!(cost, solution.cost, "problem {}", problem.id);
+    assert!(weight <= problem.max_weight, "problem {}", problem.id);
+    weight
+}
+
+pub fn optimum(problem: &Problem) -> u32 {
+    DynamicWeightSolver().construction(problem).cost
+}
+
+// solver has to agree with dynamic-weight on edge cases and small random problems
+pub fn assert_exact<S: SolverTrait>(solver: &S) {
+    edge_problems()
+        .iter()
+        .chain(random_problems(7, 20, 15).iter())
+        .for_each(|problem| {
+            let solution = solver.construction(problem);
+            check_solution(problem, &solution);
+            assert_eq!(solution.cost, optimum(problem), "problem {}", problem.id);
+        });
+}
+
+// solver has to return fitting solution, returns costs of solutions and optima
+pub fn assert_feasible<S: SolverTrait>(solver: &S) -> Vec<(u32, u32)> {
+    edge_problems()
+        .iter()
+        .chain(random_problems(7, 20, 15).iter())
+        .map(|problem| {
+            let solution = solver.construction(problem);
+            check_solution(problem, &solution);
+            let optimum = optimum(problem);
+            assert!(solution.cost <= optimum, "problem {}", problem.id);
+            (solution.cost, optimum)
+        })
+        .collect()
+}
+
+#[test]
+fn dynamic_weight_agrees_with_naive() {
+    use super::naive::NaiveSolver;
+    edge_problems()
+        .iter()
+        .chain(random_problems(3, 20, 12).iter())
+        .for_each(|problem| {
+            let solution = DynamicWeightSolver().construction(problem);
+            check_solution(problem, &solution);
+            assert_eq!(solution.cost, NaiveSolver().construction(problem).cost);
+        });
+}
@@ -1,8 +1,14 @@
 use super::{Item, Problem};
 use itertools::Itertools;
+use rand::{rngs::StdRng, SeedableRng};
 use std::cmp::Reverse;
 
-pub fn calculate_practical_ftpas_error(problem: &Problem, gcd: u32) -> u32 {
+// every problem gets own generator, so its result doesn't depend on order of problems in file
+pub fn seeded_rng(seed: u64, problem: &Problem) -> StdRng {
+    StdRng::seed_from_u64(seed ^ problem.id as u64)
+}
+
+pub fn calculate_practical_ftpas_error(problem: &Problem, gcd: u32) -> u64 {
     use itertools::FoldWhile::{Continue, Done};
     #[allow(deprecated)] // fold_while no longer deprecated in master
     let m = problem
@@ -22,12 +28,22 @@ pub fn calculate_practical_ftpas_error(problem: &Problem, gcd: u32) -> u32 {
         .items
         .iter()
         .filter(|item| item.weight <= problem.max_weight)
-        .map(|item| item.cost % gcd)
+        .map(|item| (item.cost % gcd.max(1)) as u64)
         .sorted_by_key(|&x| Reverse(x))
         .collect::<Vec<_>>();
     gcds[0..m].iter().sum()
 }
 
+// each item which fits can lose at most gcd - 1 by division
+pub fn calculate_theoretical_ftpas_error(problem: &Problem, gcd: u32) -> u64 {
+    problem
+        .items
+        .iter()
+        .filter(|item| item.weight <= problem.max_weight)
+        .count() as u64
+        * gcd.saturating_sub(1) as u64
+}
+
 // returns (new items, cost/weight ratios descending, mapping [new array] -> [original array])
 pub fn sort_by_cost_weight_ratio(items: &[Item], max_weight: u32) -> (Vec<Item>, Vec<usize>) {
     items
@@ -51,7 +67,7 @@ pub fn max_cost_from_rem(rem_costs: &[u32], rem_weights: &[u32], max_weight: u32
             .find(|(_, w)| *w <= max_weight)
             .map(|x| x.0)
             .unwrap_or(rem_costs.len() - 2)
-    } as usize;
+    };
     let rem_costs = &rem_costs[skip..];
     let rem_weights = &rem_weights[skip..];
     let mut l = 0;
@@ -72,12 +88,8 @@ pub fn max_cost_from_rem(rem_costs: &[u32], rem_weights: &[u32], max_weight: u32
     let rem_weight = (max_weight + rem_weights[l] - rem_weights[0]).min(rem_weights[0]);
     let last_weight = rem_weights[l] - rem_weights[l + 1];
     let last_cost = rem_costs[l] - rem_costs[l + 1];
-    rem_costs[0] - rem_costs[l]
-        + if rem_weight == 0 {
-            0
-        } else {
-            last_cost * last_weight / rem_weight
-        }
+    // fraction of the first item which doesn't fit
+    rem_costs[0] - rem_costs[l] + (last_cost as u64 * rem_weight as u64 / last_weight as u64) as u32
 }
 
 // Calculates maximum possible cost ... takes sorted items by cost/weight ratios
@@ -93,7 +105,10 @@ pub fn max_cost(items: &[Item], max_weight: u32) -> u32 {
             } else if weight == max_weight {
                 Done((0, cost))
             } else {
-                Done((0, cost + x.cost * x.weight / (max_weight - weight)))
+                Done((
+                    0,
+                    cost + (x.cost as u64 * (max_weight - weight) as u64 / x.weight as u64) as u32,
+                ))
             }
         })
         .into_inner()
@@ -143,3 +158,62 @@ pub fn calc_remaining_weight(items: &[Item]) -> Vec<u32> {
 pub fn calc_remaining_cost(items: &[Item]) -> Vec<u32> {
     desc_sum_vec_with_fn(items, |item| item.cost)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use super::*;
+
+    #[test]
+    fn ftpas_errors_of_zero_and_unit_divisor() {
+        let problem = problem(0, 10, &[(3, 7), (4, 9), (20, 5)]);
+        assert_eq!(calculate_theoretical_ftpas_error(&problem, 0), 0);
+        assert_eq!(calculate_theoretical_ftpas_error(&problem, 1), 0);
+        assert_eq!(calculate_practical_ftpas_error(&problem, 0), 0);
+        assert_eq!(calculate_practical_ftpas_error(&problem, 1), 0);
+    }
+
+    #[test]
+    fn ftpas_errors_count_fitting_items() {
+        let problem = problem(0, 10, &[(3, 7), (4, 9), (20, 5)]);
+        assert_eq!(calculate_theoretical_ftpas_error(&problem, 5), 2 * 4);
+        assert_eq!(calculate_practical_ftpas_error(&problem, 5), 4 + 2);
+    }
+
+    #[test]
+    fn theoretical_ftpas_error_does_not_overflow() {
+        let problem = problem(0, 10, &[(1, 1); 3]);
+        assert_eq!(
+            calculate_theoretical_ftpas_error(&problem, u32::MAX),
+            3 * (u32::MAX as u64 - 1)
+        );
+    }
+
+    #[test]
+    fn rng_depends_on_seed_and_problem_id() {
+        use rand::Rng;
+        let problems = random_problems(1, 2, 5);
+        let draw = |seed, problem| seeded_rng(seed, problem).gen::<u64>();
+        assert_eq!(draw(3, &problems[0]), draw(3, &problems[0]));
+        assert_ne!(draw(3, &problems[0]), draw(3, &problems[1]));
+        assert_ne!(draw(3, &problems[0]), draw(4, &problems[0]));
+    }
+
+    // before the fix fraction of break item was cost * weight / free capacity
+    #[test]
+    fn dantzig_fraction_of_break_item() {
+        let items = problem(0, 15, &[(10, 10), (10, 9)]).items;
+        assert_eq!(max_cost(&items, 15), 10 + 9 * 5 / 10);
+        let (costs, weights) = (calc_remaining_cost(&items), calc_remaining_weight(&items));
+        assert_eq!(max_cost_from_rem(&costs, &weights, 15), 14);
+    }
+
+    // cost * weight of break item overflowed u32
+    #[test]
+    fn dantzig_of_big_items_does_not_overflow() {
+        let items = problem(0, 80_000, &[(70_000, 70_000), (70_000, 69_000)]).items;
+        assert_eq!(max_cost(&items, 80_000), 70_000 + 9_857);
+        let (costs, weights) = (calc_remaining_cost(&items), calc_remaining_weight(&items));
+        assert_eq!(max_cost_from_rem(&costs, &weights, 80_000), 79_857);
+    }
+}